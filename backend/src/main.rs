@@ -1,4 +1,9 @@
 use moon::*;
+use moon::actix_web::{dev::RequestHead, guard, http::header, web, HttpRequest, HttpResponse};
+use shared::{negotiate_language, Language, LANGUAGE_COOKIE};
+
+/// Routes of the primary language, which has no path prefix.
+const UNPREFIXED_ROUTES: [&str; 3] = ["/", "/shaders", "/shaders/{title}"];
 
 async fn frontend() -> Frontend {
     Frontend::new()
@@ -18,7 +23,36 @@ async fn frontend() -> Frontend {
 
 async fn up_msg_handler(_: UpMsgRequest<()>) {}
 
+/// The stored choice, then `Accept-Language`.
+fn negotiated_language(head: &RequestHead) -> Language {
+    let header = |name| head.headers().get(name).and_then(|value| value.to_str().ok());
+    let cookie = header(header::COOKIE).and_then(|cookies| {
+        cookies.split(';').find_map(|cookie| cookie.trim().strip_prefix(LANGUAGE_COOKIE)?.strip_prefix('='))
+    });
+    negotiate_language(cookie, header(header::ACCEPT_LANGUAGE))
+}
+
+/// Sends a visitor who landed on an unprefixed page to the same page in their language before anything renders.
+/// Prefixed pages were asked for explicitly and stay.
+async fn redirect_to_language(request: HttpRequest) -> HttpResponse {
+    let mut location = negotiated_language(request.head()).localize(request.path());
+    if !request.query_string().is_empty() {
+        location = format!("{location}?{}", request.query_string());
+    }
+
+    HttpResponse::TemporaryRedirect()
+        .insert_header((header::LOCATION, location))
+        // The answer depends on these request headers
+        .insert_header((header::VARY, "Accept-Language, Cookie"))
+        .finish()
+}
+
 #[moon::main]
 async fn main() -> std::io::Result<()> {
-    start(frontend, up_msg_handler, |_| {}).await
+    start(frontend, up_msg_handler, |cfg| {
+        cfg.service(web::resource(UNPREFIXED_ROUTES)
+            // Visitors negotiating the primary language fall through to the frontend
+            .guard(guard::fn_guard(|ctx| negotiated_language(ctx.head()) != Language::default()))
+            .route(web::get().to(redirect_to_language)));
+    }).await
 }
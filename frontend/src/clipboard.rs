@@ -0,0 +1,49 @@
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen_futures::JsFuture;
+use zoon::*;
+use crate::theme;
+
+/// How long the button reports the result before going back to its label.
+const FEEDBACK_MS: u32 = 2000;
+
+/// Writes `text` to the system clipboard. `navigator.clipboard` is called through `Reflect`
+/// because web-sys only exposes it behind `web_sys_unstable_apis`.
+pub async fn copy_text(text: &str) -> Result<(), JsValue> {
+    let clipboard = Reflect::get(&window().navigator(), &JsValue::from_str("clipboard"))?;
+    let write_text = Reflect::get(&clipboard, &JsValue::from_str("writeText"))?.dyn_into::<Function>()?;
+    let promise = write_text.call1(&clipboard, &JsValue::from_str(text))?.dyn_into::<Promise>()?;
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+// ------ View ------
+
+/// Copies what `text` returns at the time of the press.
+pub fn copy_button(label: &'static str, text: impl Fn() -> String + 'static) -> impl Element {
+    // Some(succeeded) while the result is shown
+    let copied: Mutable<Option<bool>> = Mutable::new(None);
+
+    Button::new()
+        .s(Padding::new().x(10).y(4))
+        .s(Font::new().size(14))
+        .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+        .s(RoundedCorners::all(4))
+        .label_signal(copied.signal().map(move |copied| match copied {
+            None => label,
+            Some(true) => "Copied",
+            Some(false) => "Copy failed",
+        }))
+        .update_raw_el(|el| el.attr("aria-live", "polite"))
+        .on_press(move || {
+            let text = text();
+            Task::start(clone!((copied) async move {
+                let result = copy_text(&text).await;
+                if let Err(error) = &result {
+                    eprintln!("Failed to copy to clipboard: {:?}", error);
+                }
+                copied.set(Some(result.is_ok()));
+                Timer::sleep(FEEDBACK_MS).await;
+                copied.set(None);
+            }));
+        })
+}
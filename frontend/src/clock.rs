@@ -0,0 +1,8 @@
+use zoon::{*, web_sys::Performance};
+
+/// Returns a function reading milliseconds from `performance.now()`. `Date` only has whole milliseconds,
+/// but keeps animations running where `performance` is missing.
+pub fn clock() -> impl Fn() -> f64 {
+    let performance = window().performance();
+    move || performance.as_ref().map_or_else(js_sys::Date::now, Performance::now)
+}
@@ -4,10 +4,45 @@ use anyhow::Result;
 use gloo_net::http::Request;
 use serde::Deserialize;
 
+/// Fields other than `title` and `description` were added later and default when older entries lack them.
 #[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct ShaderContent {
+    /// The work's slug.
+    #[serde(default)]
+    pub id: String,
     pub title: String,
     pub description: ContentI18ned,
+    /// ISO 8601 timestamps maintained by the CMS.
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub techniques: Vec<String>,
+    #[serde(default)]
+    pub references: Vec<ExternalLink>,
+    #[serde(default)]
+    pub source_links: Vec<ExternalLink>,
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Picked for the top of the gallery's "Featured" order.
+    #[serde(default)]
+    pub featured: bool,
+}
+
+/// microCMS list response. Other pagination fields are ignored.
+#[derive(Deserialize)]
+struct ShaderContents {
+    contents: Vec<ShaderContent>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ExternalLink {
+    pub title: String,
+    pub url: String,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -27,3 +62,16 @@ pub async fn fetch_shader_content<'a>(slug: impl zoon::IntoCowStr<'a> + std::fmt
 
     Ok(shader_content)
 }
+
+/// Every work's content at once, for the gallery. microCMS caps a page at 100 entries.
+pub async fn fetch_shader_contents() -> Result<Vec<ShaderContent>> {
+    let url = format!("{}shaders?limit=100", env!("MICROCMS_API_ENDPOINT"));
+    let response = Request::get(&url)
+        .header("X-MICROCMS-API-KEY", env!("MICROCMS_API_KEY"))
+        .send()
+        .await?;
+    let json: serde_json::Value = response.json().await?;
+    let shader_contents: ShaderContents = serde_json::from_value(json)?;
+
+    Ok(shader_contents.contents)
+}
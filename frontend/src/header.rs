@@ -1,6 +1,6 @@
 use zoon::*;
 
-use crate::{theme, SCREEN_WIDTH};
+use crate::{i18n::{self, Language, LANGUAGE}, shaders, theme, SCREEN_WIDTH};
 
 pub fn header() -> impl Element {
     Row::with_tag(Tag::Header)
@@ -9,20 +9,25 @@ pub fn header() -> impl Element {
         .s(Align::new().left().center_y())
         .item(logo())
         .item(theme::theme_toggle_switch())
+        .item(i18n::language_toggle_switch())
+        .item(shaders::ambient_background_toggle_switch())
 }
 
 fn logo() -> impl Element {
-    Link::new().to("/").label(
+    Link::new().to_signal(i18n::localized_url_signal("/".to_string())).label(
         Row::new()
             .s(AlignContent::new().left())
             .s(Gap::new().x(14))
             .item(logo_svg())
             .item(El::new()
-                .child_signal(SCREEN_WIDTH.signal().map(|w| if w < 360 {
-                    sitename_compact_svg()
-                } else {
-                    sitename_jp_svg()
-                })))
+                .child_signal(map_ref! {
+                    let width = SCREEN_WIDTH.signal(),
+                    let language = LANGUAGE.signal() => match (*width < 360, language) {
+                        (true, _) => sitename_compact_svg(),
+                        (false, Language::Ja) => sitename_jp_svg(),
+                        (false, Language::En) => sitename_en_svg(),
+                    }
+                }))
     )
 }
 
@@ -42,13 +47,13 @@ fn sitename_jp_svg() -> RawSvgEl<web_sys::SvgsvgElement> {
         .attr_signal("fill", theme::primary_text_color_hex())
 }
 
-// fn sitename_en_svg() -> RawSvgEl<web_sys::SvgsvgElement> {
-//     RawSvgEl::from_markup(include_str!("../../public/sitename_en.svg"))
-//         .unwrap_throw()
-//         .attr("width", "237")
-//         .attr("height", "18")
-//         .attr_signal("fill", theme::primary_text_color_hex())
-// }
+fn sitename_en_svg() -> RawSvgEl<web_sys::SvgsvgElement> {
+    RawSvgEl::from_markup(include_str!("../../public/sitename_en.svg"))
+        .unwrap_throw()
+        .attr("width", "237")
+        .attr("height", "18")
+        .attr_signal("fill", theme::primary_text_color_hex())
+}
 
 fn sitename_compact_svg() -> RawSvgEl<web_sys::SvgsvgElement> {
     RawSvgEl::from_markup(include_str!("../../public/sitename_compact.svg"))
@@ -0,0 +1,84 @@
+use shared::LANGUAGE_COOKIE;
+use zoon::{*, web_sys::HtmlDocument};
+use crate::{theme, Route, ROUTER};
+
+pub use shared::Language;
+
+/// A year, in seconds.
+const COOKIE_MAX_AGE: u32 = 60 * 60 * 24 * 365;
+
+// ------ Events ------
+
+#[derive(Clone, Copy)]
+struct ToggleLanguageClicked;
+
+// ------ States ------
+
+/// Follows the route; the header toggle switches it by navigating to the other language's route.
+pub static LANGUAGE: Lazy<Mutable<Language>> = Lazy::new(|| {
+    on(|ToggleLanguageClicked| {
+        let language = LANGUAGE.get().toggled();
+        store_language(language);
+        match ROUTER.route().get_cloned() {
+            KnownRoute(route) => ROUTER.go(route.with_language(language)),
+            _ => set_language(language),
+        }
+    });
+    Mutable::new(Language::default())
+});
+
+pub fn set_language(language: Language) {
+    LANGUAGE.set_neq(language);
+    document().document_element().unwrap_throw().set_attribute("lang", language.code()).unwrap_throw();
+}
+
+/// Remembered in a cookie, which the backend prefers over `Accept-Language` on later visits.
+fn store_language(language: Language) {
+    let cookie = format!("{LANGUAGE_COOKIE}={}; path=/; max-age={COOKIE_MAX_AGE}; samesite=lax", language.code());
+    if let Err(error) = document().unchecked_into::<HtmlDocument>().set_cookie(&cookie) {
+        eprintln!("Failed to store language setting: {:?}", error);
+    }
+}
+
+// ------ Localized routes ------
+
+impl Route {
+    pub fn language(&self) -> Language {
+        match self {
+            Route::Home | Route::Shaders | Route::ShaderPage { .. } => Language::Ja,
+            Route::EnHome | Route::EnShaders | Route::EnShaderPage { .. } => Language::En,
+        }
+    }
+
+    pub fn with_language(self, language: Language) -> Self {
+        match (self, language) {
+            (Route::Home | Route::EnHome, Language::Ja) => Route::Home,
+            (Route::Home | Route::EnHome, Language::En) => Route::EnHome,
+            (Route::Shaders | Route::EnShaders, Language::Ja) => Route::Shaders,
+            (Route::Shaders | Route::EnShaders, Language::En) => Route::EnShaders,
+            (Route::ShaderPage { title } | Route::EnShaderPage { title }, Language::Ja) => Route::ShaderPage { title },
+            (Route::ShaderPage { title } | Route::EnShaderPage { title }, Language::En) => Route::EnShaderPage { title },
+        }
+    }
+}
+
+/// Site path in the current language.
+pub fn localized_url_signal(path: String) -> impl Signal<Item = String> {
+    LANGUAGE.signal().map(move |language| language.localize(&path))
+}
+
+// ------ View ------
+
+pub fn language_toggle_switch() -> impl Element {
+    Button::new()
+        .s(Width::exact(36))
+        .s(Height::exact(36))
+        .s(RoundedCorners::all(4))
+        .s(Font::new().size(14).weight(FontWeight::Bold).color_signal(theme::primary_text_color()))
+        .label_signal(LANGUAGE.signal().map(|language| language.toggled().code().to_uppercase()))
+        .update_raw_el(|el| el.attr_signal("aria-label", LANGUAGE.signal().map(|language| match language {
+            Language::Ja => "Switch to English",
+            Language::En => "日本語に切り替え",
+        })))
+        .on_press(|| emit(ToggleLanguageClicked))
+}
@@ -1,6 +1,11 @@
+mod clipboard;
+mod clock;
 mod cms;
 mod header;
+mod i18n;
+mod rich_text;
 mod theme;
+mod typing;
 mod shaders;
 
 use header::header;
@@ -18,9 +23,19 @@ pub enum Route {
     Shaders,
     #[route("shaders", title)]
     ShaderPage { title: String },
+    #[route("en")]
+    EnHome,
+    #[route("en", "shaders")]
+    EnShaders,
+    #[route("en", "shaders", title)]
+    EnShaderPage { title: String },
 }
 
-pub static ROUTER: Lazy<Router<Route>> = lazy::default();
+pub static ROUTER: Lazy<Router<Route>> = Lazy::new(|| Router::new(|route: Option<Route>| async move {
+    if let Some(route) = route {
+        i18n::set_language(route.language());
+    }
+}));
 
 // ------ Layout ------
 
@@ -58,13 +73,14 @@ fn root() -> impl Element {
         )
         .s(Background::new().color_signal(theme::primary_background_color()))
         .on_viewport_size_change(|width, _| SCREEN_WIDTH.set_neq(width))
-        // .layer(shaders::global_canvas())
+        .layer(shaders::global_canvas())
         .layer(
             Column::new()
                 .s(Width::fill().max(1200))
                 .s(Padding::new().x_signal(mobile_layout_signal().map_bool(|| 20, || 40)))
                 .s(Align::new().top().center_x())
-                .item(header())
+                // Embedded works are shown on their own
+                .item((!*shaders::EMBEDDED).then(header))
                 .item(page_content())
         )
 }
@@ -75,9 +91,9 @@ fn page_content() -> impl Element {
             NoRoute => None,
             UnknownRoute => El::new().child("404").unify_option(),
             KnownRoute(route) => match route {
-                Route::Home => shaders::page_content(None).unify_option(),
-                Route::Shaders => shaders::page_content(None).unify_option(),
-                Route::ShaderPage { title } => shaders::page_content(Some(title)).unify_option(),
+                Route::Home | Route::EnHome => shaders::page_content(None).unify_option(),
+                Route::Shaders | Route::EnShaders => shaders::page_content(None).unify_option(),
+                Route::ShaderPage { title } | Route::EnShaderPage { title } => shaders::page_content(Some(title)).unify_option(),
             }
         }))
 }
@@ -85,6 +101,7 @@ fn page_content() -> impl Element {
 // ------ Main (Init) ------
 
 pub fn init() -> impl Element {
+    Lazy::force(&i18n::LANGUAGE);
     Lazy::force(&ROUTER);
     Lazy::force(&THEME);
 
@@ -0,0 +1,243 @@
+use scraper::{node::Node, ElementRef, Html};
+use zoon::*;
+
+/// Dropped with everything inside. Any other element outside the allowlist is unwrapped to its children.
+const DROPPED_TAGS: &[&str] = &["script", "style", "iframe", "object", "embed", "template", "noscript", "svg", "math", "form", "head", "title"];
+
+// ------ Types ------
+
+/// Sanitized CMS content. Only allowlisted elements and safe URLs survive `parse`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RichNode {
+    Text(String),
+    Element { tag: RichTag, children: Vec<RichNode> },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RichTag {
+    Paragraph,
+    Break,
+    Link { href: String },
+    Code,
+    Preformatted,
+    Emphasis,
+    Strong,
+    UnorderedList,
+    OrderedList,
+    ListItem,
+    Image { src: String, alt: String },
+}
+
+impl RichTag {
+    fn from_element(element: &scraper::node::Element) -> Option<Self> {
+        Some(match element.name() {
+            "p" => RichTag::Paragraph,
+            "br" => RichTag::Break,
+            "a" => RichTag::Link { href: safe_url(element.attr("href")?, true)? },
+            "code" => RichTag::Code,
+            "pre" => RichTag::Preformatted,
+            "em" | "i" => RichTag::Emphasis,
+            "strong" | "b" => RichTag::Strong,
+            "ul" => RichTag::UnorderedList,
+            "ol" => RichTag::OrderedList,
+            "li" => RichTag::ListItem,
+            "img" => RichTag::Image {
+                src: safe_url(element.attr("src")?, false)?,
+                alt: element.attr("alt").unwrap_or_default().to_string(),
+            },
+            _ => return None,
+        })
+    }
+
+    fn html_tag(&self) -> &'static str {
+        match self {
+            RichTag::Paragraph => "p",
+            RichTag::Break => "br",
+            RichTag::Link { .. } => "a",
+            RichTag::Code => "code",
+            RichTag::Preformatted => "pre",
+            RichTag::Emphasis => "em",
+            RichTag::Strong => "strong",
+            RichTag::UnorderedList => "ul",
+            RichTag::OrderedList => "ol",
+            RichTag::ListItem => "li",
+            RichTag::Image { .. } => "img",
+        }
+    }
+}
+
+/// Relative, `http(s)` and, for links, `mailto` URLs. Everything else (`javascript:`, `data:`...) is refused.
+pub fn safe_url(url: &str, is_link: bool) -> Option<String> {
+    let url = url.trim();
+    let lowercase = url.to_ascii_lowercase();
+    let is_safe = lowercase.starts_with("https://")
+        || lowercase.starts_with("http://")
+        || (is_link && lowercase.starts_with("mailto:"))
+        || (url.starts_with('/') && !url.starts_with("//"))
+        || (is_link && url.starts_with('#'));
+    is_safe.then(|| url.to_string())
+}
+
+// ------ Parsing ------
+
+pub fn parse(markup: &str) -> Vec<RichNode> {
+    let fragment = Html::parse_fragment(markup);
+    parse_children(fragment.root_element())
+}
+
+fn parse_children(parent: ElementRef) -> Vec<RichNode> {
+    let mut nodes = Vec::new();
+    for child in parent.children() {
+        match child.value() {
+            Node::Text(text) => nodes.push(RichNode::Text(text.text.to_string())),
+            Node::Element(element) => {
+                if DROPPED_TAGS.contains(&element.name()) {
+                    continue;
+                }
+                let Some(child) = ElementRef::wrap(child) else { continue };
+                let children = parse_children(child);
+                match RichTag::from_element(element) {
+                    Some(tag) => nodes.push(RichNode::Element { tag, children }),
+                    None => nodes.extend(children),
+                }
+            },
+            _ => {},
+        }
+    }
+    nodes
+}
+
+/// Characters revealed one by one. An image counts as one.
+pub fn text_len(nodes: &[RichNode]) -> usize {
+    nodes.iter()
+        .map(|node| match node {
+            RichNode::Text(text) => text.chars().count(),
+            RichNode::Element { tag: RichTag::Image { .. }, .. } => 1,
+            RichNode::Element { children, .. } => text_len(children),
+        })
+        .sum()
+}
+
+/// Text content without markup, for searching. An image becomes a space, one character as in `text_len`.
+pub fn plain_text(nodes: &[RichNode]) -> String {
+    nodes.iter()
+        .map(|node| match node {
+            RichNode::Text(text) => text.clone(),
+            RichNode::Element { tag: RichTag::Image { .. }, .. } => " ".to_string(),
+            RichNode::Element { children, .. } => plain_text(children),
+        })
+        .collect()
+}
+
+// ------ View ------
+
+/// Renders `nodes` showing only the first `revealed` characters, so typing can progress across elements.
+/// Set `revealed` to `usize::MAX` to show everything.
+pub fn rich_text(nodes: &[RichNode], revealed: &Mutable<usize>) -> Vec<RawElOrText> {
+    let mut offset = 0;
+    render_nodes(nodes, revealed, &mut offset)
+}
+
+fn render_nodes(nodes: &[RichNode], revealed: &Mutable<usize>, offset: &mut usize) -> Vec<RawElOrText> {
+    nodes.iter().map(|node| render_node(node, revealed, offset)).collect()
+}
+
+fn render_node(node: &RichNode, revealed: &Mutable<usize>, offset: &mut usize) -> RawElOrText {
+    let start = *offset;
+    match node {
+        RichNode::Text(text) => {
+            let chars = text.chars().collect::<Vec<_>>();
+            *offset += chars.len();
+            RawHtmlEl::new("span")
+                .child_signal(revealed.signal().map(move |revealed| {
+                    let visible = revealed.saturating_sub(start).min(chars.len());
+                    chars[..visible].iter().collect::<String>()
+                }).dedupe_cloned())
+                .into()
+        },
+        RichNode::Element { tag, children } => {
+            let mut el = RawHtmlEl::new(tag.html_tag())
+                // Elements appear once typing reaches them, so empty list bullets don't show up early
+                .style_signal("display", revealed.signal().map(move |revealed| (revealed <= start).then_some("none")).dedupe());
+            match tag {
+                RichTag::Link { href } => {
+                    el = el.attr("href", href);
+                    if href.starts_with("http") {
+                        el = el.attr("target", "_blank").attr("rel", "noopener noreferrer");
+                    }
+                },
+                RichTag::Image { src, alt } => {
+                    *offset += 1;
+                    el = el.attr("src", src).attr("alt", alt).style("max-width", "100%");
+                },
+                _ => {},
+            }
+            el.children(render_nodes(children, revealed, offset)).into()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> RichNode {
+        RichNode::Text(text.to_string())
+    }
+
+    fn element(tag: RichTag, children: Vec<RichNode>) -> RichNode {
+        RichNode::Element { tag, children }
+    }
+
+    #[test]
+    fn refuses_script_data_and_protocol_relative_urls() {
+        for url in ["javascript:alert(1)", " JavaScript:alert(1)", "data:text/html;base64,PHNjcmlwdD4=", "//evil.example/x.png", "vbscript:x", "evil.example"] {
+            assert_eq!(safe_url(url, true), None, "{url}");
+            assert_eq!(safe_url(url, false), None, "{url}");
+        }
+        assert_eq!(safe_url(" https://example.com/a ", true).as_deref(), Some("https://example.com/a"));
+        assert_eq!(safe_url("/images/a.png", false).as_deref(), Some("/images/a.png"));
+        // Only links may point at mail addresses and fragments
+        assert_eq!(safe_url("mailto:me@example.com", true).as_deref(), Some("mailto:me@example.com"));
+        assert_eq!(safe_url("mailto:me@example.com", false), None);
+        assert_eq!(safe_url("#top", false), None);
+    }
+
+    #[test]
+    fn drops_unsafe_links_and_images() {
+        let nodes = parse(r#"<a href="javascript:alert(1)">click</a><a href="//evil.example">far</a><img src="data:image/png;base64,AAAA"><img src="//evil.example/x.png" alt="x">"#);
+        // A refused link keeps its text; a refused image leaves nothing
+        assert_eq!(nodes, [text("click"), text("far")]);
+        assert_eq!(parse(r#"<a>no href</a>"#), [text("no href")]);
+    }
+
+    #[test]
+    fn keeps_safe_links_and_images() {
+        let nodes = parse(r#"<a href="https://example.com">site</a><img src="/a.png" alt="A">"#);
+        assert_eq!(nodes, [
+            element(RichTag::Link { href: "https://example.com".to_string() }, vec![text("site")]),
+            element(RichTag::Image { src: "/a.png".to_string(), alt: "A".to_string() }, vec![]),
+        ]);
+    }
+
+    #[test]
+    fn drops_script_style_and_iframe_with_their_content() {
+        let nodes = parse("<p>a<script>alert(1)</script>b</p><style>p { color: red }</style><iframe src=\"https://example.com\"><p>inner</p></iframe>c");
+        assert_eq!(nodes, [element(RichTag::Paragraph, vec![text("a"), text("b")]), text("c")]);
+    }
+
+    #[test]
+    fn unwraps_unknown_tags_to_their_children() {
+        let nodes = parse(r#"<div onclick="alert(1)"><span class="x">a<strong>b</strong></span></div><custom-tag>c</custom-tag>"#);
+        assert_eq!(nodes, [text("a"), element(RichTag::Strong, vec![text("b")]), text("c")]);
+    }
+
+    #[test]
+    fn counts_an_image_as_one_character() {
+        let nodes = parse(r#"<p>ab<img src="/a.png" alt="long alt text">cd</p>"#);
+        assert_eq!(text_len(&nodes), 5);
+        assert_eq!(plain_text(&nodes), "ab cd");
+        assert_eq!(plain_text(&nodes).chars().count(), text_len(&nodes));
+        assert_eq!(text_len(&parse("<p>日本<em>語</em></p>")), 3);
+    }
+}
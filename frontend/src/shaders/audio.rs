@@ -0,0 +1,164 @@
+use wasm_bindgen_futures::JsFuture;
+use works::{AudioAnalyzer, AudioFrame, FFT_SIZE};
+use zoon::{*, web_sys::{
+    AnalyserNode, AudioContext, AudioNode, HtmlAudioElement, MediaStream,
+    MediaStreamConstraints, MediaStreamTrack, Url,
+}};
+use crate::theme;
+use super::canvas::CanvasState;
+
+// ------ Audio input ------
+
+/// WebAudio graph feeding a work. Only time-domain samples are taken from the browser;
+/// spectrum and levels are computed by `AudioAnalyzer`.
+pub struct AudioInput {
+    context: AudioContext,
+    analyser: AnalyserNode,
+    _source: AudioNode,
+    element: Option<HtmlAudioElement>,
+    stream: Option<MediaStream>,
+    object_url: Option<String>,
+    analyzer: AudioAnalyzer,
+    samples: Vec<f32>,
+}
+
+impl AudioInput {
+    /// Plays and analyzes the file at `url` in a loop.
+    pub async fn from_url(url: &str) -> Result<Self, JsValue> {
+        let element = HtmlAudioElement::new_with_src(url)?;
+        element.set_cross_origin(Some("anonymous"));
+        element.set_loop(true);
+        let context = AudioContext::new()?;
+        let source: AudioNode = context.create_media_element_source(&element)?.into();
+        let mut input = Self::new(context, source)?;
+        // Unlike a microphone, a file should also be heard
+        input.analyser.connect_with_audio_node(&input.context.destination())?;
+        JsFuture::from(element.play()?).await?;
+        input.element = Some(element);
+
+        Ok(input)
+    }
+
+    pub async fn from_file(file: &web_sys::File) -> Result<Self, JsValue> {
+        let url = Url::create_object_url_with_blob(file)?;
+        let mut input = Self::from_url(&url).await?;
+        input.object_url = Some(url);
+
+        Ok(input)
+    }
+
+    pub async fn from_microphone() -> Result<Self, JsValue> {
+        let mut constraints = MediaStreamConstraints::new();
+        constraints.audio(&JsValue::TRUE);
+        let stream: MediaStream = JsFuture::from(window()
+            .navigator()
+            .media_devices()?
+            .get_user_media_with_constraints(&constraints)?)
+            .await?
+            .unchecked_into();
+        let context = AudioContext::new()?;
+        let source: AudioNode = context.create_media_stream_source(&stream)?.into();
+        let mut input = Self::new(context, source)?;
+        input.stream = Some(stream);
+
+        Ok(input)
+    }
+
+    fn new(context: AudioContext, source: AudioNode) -> Result<Self, JsValue> {
+        let analyser = context.create_analyser()?;
+        analyser.set_fft_size(FFT_SIZE as u32);
+        source.connect_with_audio_node(&analyser)?;
+
+        Ok(Self {
+            analyzer: AudioAnalyzer::new(context.sample_rate()),
+            context,
+            analyser,
+            _source: source,
+            element: None,
+            stream: None,
+            object_url: None,
+            samples: vec![0.0; FFT_SIZE],
+        })
+    }
+
+    pub fn frame(&mut self) -> AudioFrame {
+        self.analyser.get_float_time_domain_data(&mut self.samples);
+        self.analyzer.analyze(&self.samples)
+    }
+}
+
+impl Drop for AudioInput {
+    fn drop(&mut self) {
+        if let Some(element) = &self.element {
+            let _ = element.pause();
+        }
+        if let Some(stream) = &self.stream {
+            for track in stream.get_tracks().iter() {
+                track.unchecked_into::<MediaStreamTrack>().stop();
+            }
+        }
+        if let Some(url) = &self.object_url {
+            let _ = Url::revoke_object_url(url);
+        }
+        let _ = self.context.close();
+    }
+}
+
+async fn start_audio(canvas_state: CanvasState, input: impl std::future::Future<Output = Result<AudioInput, JsValue>>) {
+    // Release the previous source first so two tracks never play at once
+    canvas_state.audio.set(None);
+    match input.await {
+        Ok(input) => canvas_state.audio.set(Some(input)),
+        Err(error) => eprintln!("Failed to start audio input: {:?}", error),
+    }
+}
+
+// ------ View ------
+
+/// Audio sources for works that opt in with `Inputs::audio`.
+pub fn audio_controls(canvas_state: CanvasState, track: Option<&'static str>) -> impl Element {
+    Row::new()
+        .s(Gap::new().x(12))
+        .s(Font::new().size(14))
+        .multiline()
+        .item(track.map(clone!((canvas_state) move |track| {
+            control_button("▶ Play track", move || {
+                Task::start(start_audio(canvas_state.clone(), AudioInput::from_url(&public_url(track))))
+            })
+        })))
+        .item(control_button("🎙 Microphone", clone!((canvas_state) move || {
+            Task::start(start_audio(canvas_state.clone(), AudioInput::from_microphone()))
+        })))
+        .item_signal(canvas_state.audio.signal_ref(Option::is_some).map_true(clone!((canvas_state) move || {
+            control_button("■ Stop", clone!((canvas_state) move || canvas_state.audio.set(None)))
+        })))
+        .item(El::new()
+            .s(Padding::new().x(12).y(6))
+            .s(Borders::all_signal(theme::border_color().map(|color| Border::new().dashed().color(color))))
+            .s(RoundedCorners::all(4))
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child("Drop an audio file here")
+            .update_raw_el(move |el| el
+                .event_handler_with_options(EventOptions::new().preventable(), |event: events::DragOver| {
+                    event.prevent_default();
+                })
+                .event_handler_with_options(EventOptions::new().preventable(), move |event: events::Drop| {
+                    event.prevent_default();
+                    let Some(file) = event.data_transfer().and_then(|data| data.files()).and_then(|files| files.get(0)) else {
+                        return
+                    };
+                    Task::start(clone!((canvas_state) async move {
+                        start_audio(canvas_state, AudioInput::from_file(&file)).await
+                    }));
+                })
+            ))
+}
+
+fn control_button(label: &'static str, on_press: impl FnMut() + 'static) -> impl Element {
+    Button::new()
+        .s(Padding::new().x(12).y(6))
+        .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+        .s(RoundedCorners::all(4))
+        .label(label)
+        .on_press(on_press)
+}
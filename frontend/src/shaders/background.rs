@@ -0,0 +1,103 @@
+use icondata::RiPaletteDesignLine;
+use works::Slug;
+use zoon::*;
+use crate::{theme::{self, Theme, THEME}, typing::REDUCED_MOTION};
+use super::canvas::CanvasState;
+
+/// Cheap enough to run behind every page.
+const AMBIENT_WORK: Slug = Slug::GlslsandboxExample;
+const STORAGE_KEY: &str = "ambient-background";
+
+// ------ Events ------
+
+#[derive(Clone, Copy)]
+struct ToggleAmbientBackgroundClicked;
+
+// ------ States ------
+
+pub static AMBIENT_BACKGROUND: Lazy<Mutable<bool>> = Lazy::new(|| {
+    on(|ToggleAmbientBackgroundClicked| {
+        let enabled = !AMBIENT_BACKGROUND.get();
+        AMBIENT_BACKGROUND.set(enabled);
+        if let Err(error) = local_storage().insert(STORAGE_KEY, &enabled) {
+            eprintln!("Failed to store ambient background setting: {:?}", error);
+        }
+    });
+    let enabled = match local_storage().get(STORAGE_KEY) {
+        Some(Ok(enabled)) => enabled,
+        _ => !REDUCED_MOTION.get(),
+    };
+    Mutable::new(enabled)
+});
+
+// ------ View ------
+
+/// Full-viewport layer behind the page content.
+pub fn global_canvas() -> impl Element {
+    El::new().child_signal(AMBIENT_BACKGROUND.signal().map_true(ambient_canvas))
+}
+
+fn ambient_canvas() -> impl Element {
+    let canvas_state = CanvasState::default();
+    // Rendered at CSS resolution to keep the background cheap
+    canvas_state.pixel_ratio.set(Some(1.0));
+    let client_pointer = Mutable::new((0, 0));
+
+    Canvas::new()
+        .width(40)
+        .s(Width::fill())
+        .s(Height::screen())
+        .update_raw_el(clone!((canvas_state) move |el| el
+            .style("position", "fixed")
+            .style("inset", "0")
+            .style("pointer-events", "none")
+            .style_signal("opacity", THEME.signal().map(|theme| match theme {
+                Theme::Light => "0.2",
+                Theme::Dark => "0.3",
+            }))
+            .style_signal("mix-blend-mode", THEME.signal().map(|theme| match theme {
+                Theme::Light => "multiply",
+                Theme::Dark => "screen",
+            }))
+            .on_resize(clone!((canvas_state) move |w, h| canvas_state.set_size(w, h)))
+            // Pointer in page coordinates, so scrolling pans the work as well
+            .global_event_handler(clone!((canvas_state, client_pointer) move |event: events_extra::PointerMove| {
+                client_pointer.set_neq((event.client_x(), event.client_y()));
+                canvas_state.pointer_position.set_neq(page_pointer(client_pointer.get()));
+            }))
+            .global_event_handler(clone!((canvas_state, client_pointer) move |_: events::Scroll| {
+                canvas_state.pointer_position.set_neq(page_pointer(client_pointer.get()));
+            }))
+        ))
+        .after_insert(clone!((canvas_state) move |canvas| Task::start(canvas_state.run_shared(canvas, AMBIENT_WORK))))
+        .after_remove(move |_| canvas_state.stop())
+}
+
+fn page_pointer((x, y): (i32, i32)) -> (i32, i32) {
+    (x, y + window().scroll_y().unwrap_or_default() as i32)
+}
+
+pub fn ambient_background_toggle_switch() -> impl Element {
+    Checkbox::new()
+        .id("ambient-background-toggle-switch")
+        .label_hidden("Ambient background")
+        .icon(|checked| checkbox_icon(checked.signal()))
+        .checked_signal(AMBIENT_BACKGROUND.signal())
+        .on_change(|_| emit(ToggleAmbientBackgroundClicked))
+}
+
+fn checkbox_icon(checked_signal: MutableSignal<bool>) -> impl Element {
+    static ICON: &str = RiPaletteDesignLine.data;
+
+    El::new()
+        .s(Width::exact(36))
+        .s(Height::exact(36))
+        .s(AlignContent::center())
+        .s(RoundedCorners::all(4))
+        .child(RawSvgEl::new("svg")
+            .style("width", "24px")
+            .style_signal("opacity", checked_signal.map_bool(|| "1", || "0.4"))
+            .attr("viewBox", "0 0 24 24")
+            .attr_signal("fill", theme::primary_text_color_hex())
+            .inner_markup(ICON))
+}
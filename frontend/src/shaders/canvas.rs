@@ -0,0 +1,316 @@
+use std::cmp::max;
+use std::rc::Rc;
+use works::{FrameInput, Gpu, GpuContext, GpuInitError, KeyboardState, Palette, PointerState, Requirements, Slug};
+use wgpu::{Backends, Instance, InstanceDescriptor, RequestAdapterOptions, Surface, SurfaceError, SurfaceTarget};
+use crate::{clock, theme::{Theme, THEME}};
+use super::audio::AudioInput;
+use super::governor::{Governor, Quality};
+use super::keyboard::KeyboardListener;
+use super::pointer::PointerListener;
+use super::resolution::{DEVICE_PIXEL_RATIO, RENDER_SCALE};
+use super::stats::{FrameStats, TIMING_OVERLAY};
+use super::visibility::{VisibilityObserver, PAGE_VISIBLE};
+#[allow(unused_imports)]
+use zoon::{web_sys::HtmlCanvasElement, println, eprintln, *};
+
+// ------ GPU ------
+
+/// Device shared by every canvas that opts in through `CanvasState::run_shared`.
+static SHARED_GPU: Lazy<Mutable<Option<Rc<Gpu>>>> = lazy::default();
+
+/// Prefers WebGPU and falls back to WebGL2. The WebGPU device is requested before the canvas gets a context,
+/// because a canvas that handed out a `webgpu` context can't hand out a `webgl2` one anymore.
+async fn create_gpu(canvas: &HtmlCanvasElement, requirements: &Requirements) -> Result<(Gpu, Surface<'static>), GpuInitError> {
+    let instance = Instance::new(InstanceDescriptor { backends: Backends::BROWSER_WEBGPU, ..Default::default() });
+    if let Some(adapter) = instance.request_adapter(&RequestAdapterOptions::default()).await {
+        match Gpu::with_adapter(instance, adapter, requirements).await {
+            Ok(gpu) => {
+                let surface = create_surface(&gpu.instance, canvas)?;
+                return Ok((gpu, surface));
+            },
+            // WebGL2 offers even less, so an unsupported work fails here
+            Err(error @ GpuInitError::Unsupported { .. }) => return Err(error),
+            // The canvas has no context yet, so WebGL2 can still take over
+            Err(error) => eprintln!("WebGPU failed, falling back to WebGL2: {}", error),
+        }
+    }
+
+    let instance = Instance::new(InstanceDescriptor { backends: Backends::GL, ..Default::default() });
+    let surface = create_surface(&instance, canvas)?;
+    let gpu = Gpu::new(instance, Some(&surface), requirements).await?;
+    Ok((gpu, surface))
+}
+
+fn create_surface(instance: &Instance, canvas: &HtmlCanvasElement) -> Result<Surface<'static>, GpuInitError> {
+    instance.create_surface(SurfaceTarget::Canvas(canvas.clone()))
+        .map_err(GpuInitError::CreateSurface)
+}
+
+async fn gpu_context(canvas: &HtmlCanvasElement, size: (U32Width, U32Height), pixel_ratio: f64, requirements: &Requirements) -> Result<GpuContext<'static>, GpuInitError> {
+    let (gpu, surface) = create_gpu(canvas, requirements).await?;
+    GpuContext::with_gpu(&gpu, surface, size, pixel_ratio)
+}
+
+/// Only WebGPU devices are actually shared. On the WebGL2 fallback every canvas still gets a device
+/// (and a GL context) of its own, so callers have to bound how many run at once themselves:
+/// gallery previews stay within `preview::MAX_LIVE_PREVIEWS`, well below the browsers' ~16 live WebGL contexts.
+async fn shared_gpu_context(canvas: &HtmlCanvasElement, size: (U32Width, U32Height), pixel_ratio: f64, requirements: &Requirements) -> Result<GpuContext<'static>, GpuInitError> {
+    // A lost device gets replaced by the next canvas that starts
+    if let Some(gpu) = SHARED_GPU.get_cloned().filter(|gpu| !gpu.is_lost()) {
+        // Works needing more than the baseline get a device of their own
+        if !gpu.satisfies(requirements) {
+            return gpu_context(canvas, size, pixel_ratio, requirements).await;
+        }
+        let surface = create_surface(&gpu.instance, canvas)?;
+        if gpu.supports(&surface) {
+            return GpuContext::with_gpu(&gpu, surface, size, pixel_ratio);
+        }
+        // The canvas can't use the shared adapter (WebGL), so it gets a device of its own
+        return gpu_context(canvas, size, pixel_ratio, requirements).await;
+    }
+
+    let (gpu, surface) = create_gpu(canvas, requirements).await?;
+    let gpu = Rc::new(gpu);
+    // Only a baseline device is worth sharing
+    if *requirements == Requirements::default() {
+        let mut shared_gpu = SHARED_GPU.lock_mut();
+        if shared_gpu.as_ref().map_or(true, |shared_gpu| shared_gpu.is_lost()) {
+            *shared_gpu = Some(gpu.clone());
+        }
+    }
+
+    GpuContext::with_gpu(&gpu, surface, size, pixel_ratio)
+}
+
+// ------ Canvas state ------
+
+/// Longest frame delta handed to a work, so resuming after a pause doesn't jump ahead.
+const MAX_FRAME_DELTA: f32 = 0.1;
+/// Animation frames arrive on vsync with some jitter, so a frame this much early for a capped
+/// frame rate is rendered rather than held back for another whole vsync interval.
+const FRAME_INTERVAL_TOLERANCE: f32 = 0.004;
+
+/// Size, pointer, DPR and animation loop of a single canvas.
+/// Every canvas owns its own state so several works can render on the same page.
+#[derive(Clone)]
+pub struct CanvasState {
+    pub width: Mutable<U32Width>,
+    pub height: Mutable<U32Height>,
+    /// Fixed pixel ratio; `None` follows the display's ratio times `RENDER_SCALE`.
+    pub pixel_ratio: Mutable<Option<f64>>,
+    pub pointer_position: Mutable<(i32, i32)>,
+    /// Seconds into the work. Set before `run` to start at an offset; follows the frame loop after.
+    pub time: Mutable<f32>,
+    /// Palette of this canvas only; `None` follows `THEME`.
+    pub theme: Mutable<Option<Theme>>,
+    pub audio: Mutable<Option<AudioInput>>,
+    pub keyboard: Mutable<KeyboardState>,
+    keyboard_listener: Mutable<Option<KeyboardListener>>,
+    pub pointer: Mutable<PointerState>,
+    pointer_listener: Mutable<Option<PointerListener>>,
+    /// Set by the performance governor while the work runs.
+    pub quality: Mutable<Quality>,
+    pub stats: Mutable<FrameStats>,
+    /// Adapter name and backend, known once the work runs.
+    pub adapter: Mutable<Option<String>>,
+    /// Why the work can't run in this browser, if it can't.
+    pub gpu_error: Mutable<Option<GpuInitError>>,
+    on_screen: Mutable<bool>,
+    visibility_observer: Mutable<Option<VisibilityObserver>>,
+    animation_loop: Mutable<Option<AnimationLoop>>,
+}
+
+impl Default for CanvasState {
+    fn default() -> Self {
+        Self {
+            width: Mutable::new(40),
+            height: Mutable::new(30),
+            pixel_ratio: Mutable::new(None),
+            pointer_position: Mutable::new((0, 0)),
+            time: Mutable::new(0.0),
+            theme: Mutable::new(None),
+            audio: Mutable::new(None),
+            keyboard: Mutable::new(KeyboardState::default()),
+            keyboard_listener: Mutable::new(None),
+            pointer: Mutable::new(PointerState::default()),
+            pointer_listener: Mutable::new(None),
+            quality: Mutable::new(Quality::default()),
+            stats: Mutable::new(FrameStats::default()),
+            adapter: Mutable::new(None),
+            gpu_error: Mutable::new(None),
+            on_screen: Mutable::new(true),
+            visibility_observer: Mutable::new(None),
+            animation_loop: Mutable::new(None),
+        }
+    }
+}
+
+impl CanvasState {
+    pub fn size(&self) -> (U32Width, U32Height) {
+        (self.width.get(), self.height.get())
+    }
+
+    pub fn pixel_ratio(&self) -> f64 {
+        self.pixel_ratio.get().unwrap_or_else(|| DEVICE_PIXEL_RATIO.get() * RENDER_SCALE.get())
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme.get().unwrap_or_else(|| THEME.get())
+    }
+
+    pub fn set_size(&self, width: U32Width, height: U32Height) {
+        self.width.set_neq(max(width, 1));
+        self.height.set_neq(max(height, 1));
+    }
+
+    pub async fn run(self, canvas: HtmlCanvasElement, slug: Slug) {
+        self.run_with(canvas, slug, false).await
+    }
+
+    /// Like `run`, but renders with the page-wide shared device where the backend allows it.
+    /// WebGL2 can't share, so there each call still creates a device; see `shared_gpu_context`.
+    pub async fn run_shared(self, canvas: HtmlCanvasElement, slug: Slug) {
+        self.run_with(canvas, slug, true).await
+    }
+
+    /// Also restarts the work from scratch once its device is lost.
+    async fn run_with(self, canvas: HtmlCanvasElement, slug: Slug, shared: bool) {
+        let requirements = slug.requirements();
+        let ctx = match shared {
+            true => shared_gpu_context(&canvas, self.size(), self.pixel_ratio(), &requirements).await,
+            false => gpu_context(&canvas, self.size(), self.pixel_ratio(), &requirements).await,
+        };
+        // The canvas may have been removed while the device was being requested
+        if !canvas.is_connected() {
+            return;
+        }
+        match ctx {
+            Ok(ctx) => self.start(&canvas, ctx, slug, shared),
+            Err(error) => {
+                eprintln!("Failed to start {}: {}", slug, error);
+                self.gpu_error.set(Some(error));
+            },
+        }
+    }
+
+    fn start(&self, canvas: &HtmlCanvasElement, mut ctx: GpuContext<'static>, slug: Slug, shared: bool) {
+        let mut work = slug.create(&ctx);
+        // Picks up where a lost device left off, or at the requested offset
+        let mut input = FrameInput { time: self.time.get(), ..FrameInput::default() };
+        let mut theme = None;
+        let mut governor = Governor::default();
+        let mut since_render = 0.0;
+        let mut last_frame: Option<f64> = None;
+        let mut paused = false;
+        let mut rebuilding = false;
+        let clock = clock::clock();
+
+        let info = &ctx.adapter_info;
+        self.adapter.set(Some(match info.name.is_empty() {
+            true => info.backend.to_string(),
+            false => format!("{} ({})", info.name, info.backend),
+        }));
+
+        let on_screen = self.on_screen.clone();
+        self.visibility_observer.set(Some(VisibilityObserver::new(canvas, move |visible| on_screen.set_neq(visible))));
+
+        let state = self.clone();
+        let canvas = canvas.clone();
+        let animation_loop = AnimationLoop::new(move |_| {
+            // Fractional milliseconds, so rounding can't make a frame look too early for a capped rate
+            let now = clock();
+            let delta = last_frame.replace(now).map_or(0.0, |last| ((now - last) / 1000.0) as f32);
+            if rebuilding {
+                return;
+            }
+            if ctx.is_device_lost() {
+                eprintln!("Rebuilding {} on a new device.", slug);
+                rebuilding = true;
+                Task::start(state.clone().run_with(canvas.clone(), slug, shared));
+                return;
+            }
+            // Hidden tabs usually stop animation frames anyway, off-screen canvases don't
+            if !PAGE_VISIBLE.get() || !state.on_screen.get() {
+                paused = true;
+                return;
+            }
+            if paused {
+                paused = false;
+                governor.reset_samples();
+            }
+            since_render += delta.min(MAX_FRAME_DELTA);
+            if since_render + FRAME_INTERVAL_TOLERANCE < governor.frame_interval() {
+                return;
+            }
+            let frame_time = std::mem::take(&mut since_render);
+            let quality = governor.quality();
+            state.quality.set_neq(quality);
+
+            ctx.resize(state.size(), state.pixel_ratio() * quality.render_scale());
+            let current_theme = state.theme();
+            if theme != Some(current_theme) {
+                ctx.set_palette(Palette::new(current_theme.flavor()));
+                theme = Some(current_theme);
+            }
+            if let Some(audio) = state.audio.lock_mut().as_mut() {
+                ctx.audio.update(&ctx.queue, &audio.frame());
+            }
+            ctx.keyboard.update(&ctx.queue, &mut state.keyboard.lock_mut());
+            let pixel_scale = ctx.config.width as f32 / ctx.size.0 as f32;
+            ctx.pointer.update(&ctx.queue, &mut state.pointer.lock_mut(), pixel_scale);
+            let (x, y) = state.pointer_position.get();
+            input.advance(frame_time, (x as f32, y as f32));
+            state.time.set(input.time);
+
+            let Some(surface) = &ctx.surface else { return };
+            let frame = match surface.get_current_texture() {
+                Ok(frame) => frame,
+                // Try again next frame
+                Err(SurfaceError::Timeout) => return,
+                Err(SurfaceError::Outdated | SurfaceError::Lost) => {
+                    ctx.reconfigure();
+                    return;
+                },
+                Err(SurfaceError::OutOfMemory) => {
+                    eprintln!("Out of GPU memory, rebuilding {}.", slug);
+                    rebuilding = true;
+                    Task::start(state.clone().run_with(canvas.clone(), slug, shared));
+                    return;
+                },
+            };
+            work.render(&ctx, &frame.texture, &input);
+            frame.present();
+
+            if let Some(timer) = &mut ctx.timer {
+                timer.poll();
+            }
+            // Milliseconds, like the timing overlay shows them. The GPU time trails by a few frames.
+            let cpu_time = (clock() - now) as f32;
+            let gpu_time = ctx.timer.as_ref().and_then(|timer| timer.last_pass_time());
+            governor.record(cpu_time.max(gpu_time.unwrap_or(0.0)) / 1000.0, frame_time);
+            if TIMING_OVERLAY.get() {
+                state.stats.lock_mut().record(frame_time * 1000.0, cpu_time, gpu_time);
+            }
+        });
+
+        self.animation_loop.set(Some(animation_loop));
+    }
+
+    /// Forwards key events to the work while `canvas` has focus.
+    pub fn listen_keyboard(&self, canvas: &HtmlCanvasElement) {
+        self.keyboard_listener.set(Some(KeyboardListener::new(canvas, self.keyboard.clone())));
+    }
+
+    /// Forwards buttons, drags, touches and wheel scrolls on `canvas` to the work.
+    pub fn listen_pointer(&self, canvas: &HtmlCanvasElement) {
+        self.pointer_listener.set(Some(PointerListener::new(canvas, self.pointer.clone())));
+    }
+
+    pub fn stop(&self) {
+        self.animation_loop.set(None);
+        self.audio.set(None);
+        self.keyboard_listener.set(None);
+        self.pointer_listener.set(None);
+        self.visibility_observer.set(None);
+    }
+}
@@ -0,0 +1,563 @@
+use strum::IntoEnumIterator;
+use works::Slug;
+use zoon::*;
+use crate::{cms::{self, ShaderContent}, i18n::{self, Language, LANGUAGE}, rich_text, theme};
+use super::{preview, visibility::VisibilityObserver};
+
+/// Cards added each time the end of the gallery scrolls into view.
+const PAGE_SIZE: usize = 12;
+
+// ------ States ------
+
+/// Every work's CMS content, fetched once and shared by the gallery and the links between works.
+pub static CONTENTS: Lazy<Mutable<Vec<ShaderContent>>> = Lazy::new(|| {
+    Task::start(async {
+        match cms::fetch_shader_contents().await {
+            Ok(contents) => CONTENTS.set(contents),
+            Err(err) => eprintln!("Failed to fetch shader contents: {:?}", err),
+        }
+    });
+    Mutable::new(Vec::new())
+});
+
+/// What the filter sees of every work, rebuilt only when the contents or the language change
+/// rather than on every keystroke in the search box.
+pub static ENTRIES: Lazy<Mutable<Vec<GalleryEntry>>> = Lazy::new(|| {
+    Task::start(map_ref! {
+        let contents = CONTENTS.signal_cloned(),
+        let language = LANGUAGE.signal() => gallery_entries(contents, *language)
+    }.for_each_sync(|entries| ENTRIES.set(entries)));
+    Mutable::new(gallery_entries(&[], LANGUAGE.get()))
+});
+
+/// Filter the gallery was last shown with, so the links between works follow the order the visitor browsed in.
+pub static LAST_FILTER: Lazy<Mutable<GalleryFilter>> = lazy::default();
+
+// ------ Filter ------
+
+/// Selected tags, search text and sort order of the gallery, mirrored in the `/shaders` query
+/// like `?tag=sdf&tag=audio&q=noise&sort=title`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GalleryFilter {
+    pub tags: Vec<String>,
+    pub search: String,
+    pub sort: GallerySort,
+}
+
+impl GalleryFilter {
+    /// Accepts the query with or without its leading `?`. Unknown parameters are ignored.
+    pub fn from_query(query: &str) -> Self {
+        let mut filter = Self::default();
+        for pair in query.trim_start_matches('?').split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode_component(value);
+            match key {
+                "tag" if !value.is_empty() && !filter.tags.contains(&value) => filter.tags.push(value),
+                "q" => filter.search = value,
+                "sort" => filter.sort = GallerySort::from_code(&value).unwrap_or_default(),
+                _ => {},
+            }
+        }
+        filter
+    }
+
+    /// Empty when nothing is filtered, so the plain `/shaders` URL stays clean.
+    pub fn to_query(&self) -> String {
+        let search = self.search.trim();
+        let pairs = self.tags.iter()
+            .map(|tag| format!("tag={}", encode_component(tag)))
+            .chain((!search.is_empty()).then(|| format!("q={}", encode_component(search))))
+            .chain((self.sort != GallerySort::default()).then(|| format!("sort={}", self.sort.code())))
+            .collect::<Vec<_>>();
+        match pairs.is_empty() {
+            true => String::new(),
+            false => format!("?{}", pairs.join("&")),
+        }
+    }
+
+    pub fn toggle_tag(&mut self, tag: &str) {
+        match self.tags.iter().position(|selected| selected == tag) {
+            Some(index) => {
+                self.tags.remove(index);
+            },
+            None => self.tags.push(tag.to_string()),
+        }
+    }
+
+    /// An entry needs every selected tag, and every search word somewhere in its title or description.
+    /// Search ignores case.
+    pub fn matches(&self, entry: &GalleryEntry) -> bool {
+        let has_tags = self.tags.iter().all(|tag| entry.tags.contains(tag));
+        let title = entry.title.to_lowercase();
+        let description = entry.description.to_lowercase();
+        let has_words = self.search
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| title.contains(word) || description.contains(word));
+        has_tags && has_words
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GallerySort {
+    #[default]
+    Newest,
+    Oldest,
+    Title,
+    Featured,
+}
+
+impl GallerySort {
+    pub const ALL: [GallerySort; 4] = [GallerySort::Newest, GallerySort::Oldest, GallerySort::Title, GallerySort::Featured];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            GallerySort::Newest => "newest",
+            GallerySort::Oldest => "oldest",
+            GallerySort::Title => "title",
+            GallerySort::Featured => "featured",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.code() == code)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            GallerySort::Newest => "Newest",
+            GallerySort::Oldest => "Oldest",
+            GallerySort::Title => "Title",
+            GallerySort::Featured => "Featured",
+        }
+    }
+
+    /// Works without a publish date count as older than dated ones; ties keep the order works were added in.
+    pub fn sort(&self, entries: &mut [GalleryEntry]) {
+        let newest_first = |a: &GalleryEntry, b: &GalleryEntry| {
+            b.published_at.cmp(&a.published_at).then(a.position.cmp(&b.position))
+        };
+        match self {
+            GallerySort::Newest => entries.sort_by(newest_first),
+            GallerySort::Oldest => entries.sort_by(|a, b| a.published_at.cmp(&b.published_at).then(a.position.cmp(&b.position))),
+            GallerySort::Title => entries.sort_by(|a, b| {
+                (a.title.to_lowercase(), a.position).cmp(&(b.title.to_lowercase(), b.position))
+            }),
+            GallerySort::Featured => entries.sort_by(|a, b| b.featured.cmp(&a.featured).then_with(|| newest_first(a, b))),
+        }
+    }
+}
+
+/// What the filter sees of a work. Falls back to the slug's title until the CMS content arrives.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GalleryEntry {
+    pub slug: Slug,
+    /// Place of the work in `Slug`, which lists works in the order they were added.
+    pub position: usize,
+    pub title: String,
+    /// Plain text in the current language.
+    pub description: String,
+    pub tags: Vec<String>,
+    pub published_at: Option<String>,
+    pub featured: bool,
+}
+
+impl GalleryEntry {
+    pub fn new(slug: Slug, position: usize, content: Option<&ShaderContent>, language: Language) -> Self {
+        let Some(content) = content else {
+            return Self {
+                slug,
+                position,
+                title: slug.title(),
+                description: String::new(),
+                tags: Vec::new(),
+                published_at: None,
+                featured: false,
+            };
+        };
+        let markup = match language {
+            Language::Ja => &content.description.ja,
+            Language::En => &content.description.en,
+        };
+        Self {
+            slug,
+            position,
+            title: content.title.clone(),
+            description: rich_text::plain_text(&rich_text::parse(markup)),
+            tags: content.tags.clone(),
+            published_at: content.published_at.clone(),
+            featured: content.featured,
+        }
+    }
+}
+
+fn gallery_entries(contents: &[ShaderContent], language: Language) -> Vec<GalleryEntry> {
+    Slug::iter()
+        .enumerate()
+        .map(|(position, slug)| {
+            let id = slug.to_string();
+            GalleryEntry::new(slug, position, contents.iter().find(|content| content.id == id), language)
+        })
+        .collect()
+}
+
+/// Title from the CMS, or the slug's own until it arrives.
+pub fn work_title(contents: &[ShaderContent], slug: Slug) -> String {
+    let id = slug.to_string();
+    contents.iter()
+        .find(|content| content.id == id)
+        .map_or_else(|| slug.title(), |content| content.title.clone())
+}
+
+/// Slugs of the works passing `filter`, in its sort order.
+pub fn arrange(filter: &GalleryFilter, entries: &[GalleryEntry]) -> Vec<Slug> {
+    let mut entries = entries.iter()
+        .filter(|entry| filter.matches(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+    filter.sort.sort(&mut entries);
+    entries.into_iter().map(|entry| entry.slug).collect()
+}
+
+/// Every tag in use, sorted.
+fn all_tags(contents: &[ShaderContent]) -> Vec<String> {
+    let mut tags = contents.iter().flat_map(|content| content.tags.iter().cloned()).collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode_component(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Reverses `encode_component`, also reading `+` as a space. Malformed escapes are kept as they are.
+fn decode_component(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            (b'+', None) => {
+                decoded.push(b' ');
+                index += 1;
+            },
+            (byte, None) => {
+                decoded.push(byte);
+                index += 1;
+            },
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Rewrites the query without a navigation, so typing in the search box doesn't pile up history entries.
+fn replace_query(filter: &GalleryFilter) {
+    let location = window().location();
+    let (Ok(path), Ok(current)) = (location.pathname(), location.search()) else {
+        return
+    };
+    let query = filter.to_query();
+    if query == current {
+        return;
+    }
+    let url = format!("{path}{query}");
+    if let Err(error) = window().history().and_then(|history| history.replace_state_with_url(&JsValue::NULL, "", Some(&url))) {
+        eprintln!("Failed to update gallery query: {:?}", error);
+    }
+}
+
+// ------ View ------
+
+// /shaders
+pub fn shader_gallery() -> impl Element {
+    let width: Mutable<U32Width> = Mutable::new(0);
+    let filter = Mutable::new(GalleryFilter::from_query(&window().location().search().unwrap_or_default()));
+    let contents = CONTENTS.clone();
+    // Works passing the filter, in display order
+    let arranged_slugs = Mutable::new(arrange(&filter.lock_ref(), &ENTRIES.lock_ref()));
+    // Cards beyond it aren't created yet, so their thumbnails aren't loaded
+    let limit = Mutable::new(PAGE_SIZE);
+
+    let filter_task = Task::start_droppable(clone!((filter, arranged_slugs) map_ref! {
+        let filter = filter.signal_cloned(),
+        let entries = ENTRIES.signal_cloned() => arrange(filter, entries)
+    }.for_each_sync(move |slugs| arranged_slugs.set_neq(slugs))));
+    let query_task = Task::start_droppable(filter.signal_cloned().for_each_sync(|filter| {
+        replace_query(&filter);
+        LAST_FILTER.set(filter);
+    }));
+
+    Column::new()
+        .s(Width::fill())
+        .s(Gap::new().y(16))
+        .item(filter_bar(filter, contents))
+        .item_signal(arranged_slugs.signal_ref(Vec::is_empty).dedupe().map_true(|| El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child("No works match the filter.")))
+        .item(Row::new()
+            .s(Gap::both(16))
+            .multiline()
+            .on_viewport_size_change(clone!((width) move |w, _| width.set_neq(w)))
+            .items(Slug::iter()
+                .enumerate()
+                .map(|(index, slug)| {
+                    let rank = clone!((arranged_slugs) move || {
+                        arranged_slugs.signal_ref(move |slugs| slugs.iter().position(|arranged| *arranged == slug)).dedupe()
+                    });
+                    let is_shown = clone!((limit, rank) move || map_ref! {
+                        let rank = rank(),
+                        let limit = limit.signal() => rank.is_some_and(|rank| rank < *limit)
+                    }.dedupe());
+                    // Stays true once the card is first shown
+                    let mut loaded = false;
+                    let is_loaded = is_shown().map(move |shown| {
+                        loaded |= shown;
+                        loaded
+                    }).dedupe();
+                    // Created once, then only reordered and hidden, so sorting and filtering don't restart the previews
+                    El::new()
+                        .update_raw_el(|el| el
+                            .style("flex-basis", "180px")
+                            .style_signal("min-width", width.signal().map(|w| w < 376).map_true(|| "100%"))
+                            .style_signal("order", rank().map(|rank| rank.map(|rank| rank.to_string())))
+                            .style_signal("display", is_shown().map_false(|| "none"))
+                        )
+                        .child_signal(is_loaded.map_true(move || Link::new()
+                            .s(Width::fill())
+                            .to_signal(i18n::localized_url_signal(slug.page_url()))
+                            .label(preview::preview_card(index, slug))))
+                })))
+        .item(load_more_sentinel(limit, arranged_slugs))
+        .after_remove(move |_| {
+            drop(filter_task);
+            drop(query_task);
+        })
+}
+
+/// Grows `limit` by a page while the end of the gallery is in view. Observing again after every change
+/// picks up a sentinel that stays in view because the added cards didn't fill the screen.
+fn load_more_sentinel(limit: Mutable<usize>, arranged_slugs: Mutable<Vec<Slug>>) -> impl Element {
+    let observer: Mutable<Option<VisibilityObserver>> = Mutable::new(None);
+    let observe_task: Mutable<Option<TaskHandle>> = Mutable::new(None);
+
+    El::new()
+        .s(Width::fill())
+        .s(Height::exact(1))
+        .after_insert(clone!((observer, observe_task) move |el| {
+            let changes = map_ref! {
+                let loaded = limit.signal(),
+                let total = arranged_slugs.signal_ref(Vec::len) => (*loaded, *total)
+            };
+            observe_task.set(Some(Task::start_droppable(changes.for_each_sync(move |_| {
+                let (limit, arranged_slugs) = (limit.clone(), arranged_slugs.clone());
+                observer.set(Some(VisibilityObserver::new(&el, move |visible| {
+                    if visible && limit.get() < arranged_slugs.lock_ref().len() {
+                        *limit.lock_mut() += PAGE_SIZE;
+                    }
+                })));
+            }))));
+        }))
+        .after_remove(move |_| {
+            observe_task.set(None);
+            observer.set(None);
+        })
+}
+
+fn filter_bar(filter: Mutable<GalleryFilter>, contents: Mutable<Vec<ShaderContent>>) -> impl Element {
+    Column::new()
+        .s(Gap::new().y(10))
+        .item(sort_selector(filter.clone()))
+        .item(TextInput::new()
+            .s(Width::fill().max(400))
+            .s(Padding::new().x(10).y(6))
+            .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+            .s(RoundedCorners::all(4))
+            .s(Background::new().color_signal(theme::primary_background_color()))
+            .label_hidden("Search works")
+            .placeholder(Placeholder::new("Search"))
+            // Initial text only; feeding the filter back in would move the caret while typing
+            .text(filter.lock_ref().search.clone())
+            .on_change(clone!((filter) move |text| filter.lock_mut().search = text)))
+        .item(Row::new()
+            .s(Gap::both(8))
+            .s(Font::new().size(14))
+            .multiline()
+            .items_signal_vec(contents.signal_ref(|contents| all_tags(contents)).to_signal_vec().map(move |tag| {
+                let selected = filter.signal_ref(clone!((tag) move |filter| filter.tags.contains(&tag))).dedupe();
+                Button::new()
+                    .s(Padding::new().x(10).y(4))
+                    .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+                    .s(RoundedCorners::all(4))
+                    .s(Background::new().color_signal(map_ref! {
+                        let selected = selected,
+                        let color = theme::hovered_background_color() => selected.then(|| color.clone())
+                    }))
+                    .label(format!("#{tag}"))
+                    .on_press(clone!((filter) move || filter.lock_mut().toggle_tag(&tag)))
+            })))
+}
+
+fn sort_selector(filter: Mutable<GalleryFilter>) -> impl Element {
+    Row::new()
+        .s(Gap::new().x(8))
+        .s(Font::new().size(14))
+        .multiline()
+        .item(El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child("Sort"))
+        .items(GallerySort::ALL.map(|sort| {
+            let selected = filter.signal_ref(move |filter| filter.sort == sort).dedupe();
+            Button::new()
+                .s(Padding::new().x(10).y(4))
+                .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+                .s(RoundedCorners::all(4))
+                .s(Background::new().color_signal(map_ref! {
+                    let selected = selected,
+                    let color = theme::hovered_background_color() => selected.then(|| color.clone())
+                }))
+                .label(sort.label())
+                .on_press(clone!((filter) move || filter.lock_mut().sort = sort))
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(slug: Slug, title: &str, description: &str, tags: &[&str]) -> GalleryEntry {
+        GalleryEntry {
+            slug,
+            position: 0,
+            title: title.to_string(),
+            description: description.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            published_at: None,
+            featured: false,
+        }
+    }
+
+    #[test]
+    fn empty_filter_has_empty_query() {
+        assert_eq!(GalleryFilter::default().to_query(), "");
+        assert_eq!(GalleryFilter { search: "  ".to_string(), ..GalleryFilter::default() }.to_query(), "");
+        assert_eq!(GalleryFilter::from_query(""), GalleryFilter::default());
+        assert_eq!(GalleryFilter::from_query("?"), GalleryFilter::default());
+    }
+
+    #[test]
+    fn query_round_trip() {
+        let filter = GalleryFilter {
+            tags: vec!["sdf".to_string(), "audio & noise".to_string(), "日本".to_string()],
+            search: "warm+glow 100%".to_string(),
+            sort: GallerySort::Title,
+        };
+        let query = filter.to_query();
+        assert_eq!(query, "?tag=sdf&tag=audio%20%26%20noise&tag=%E6%97%A5%E6%9C%AC&q=warm%2Bglow%20100%25&sort=title");
+        assert_eq!(GalleryFilter::from_query(&query), filter);
+        assert_eq!(GalleryFilter::from_query(query.trim_start_matches('?')), filter);
+    }
+
+    #[test]
+    fn decodes_plus_and_escapes() {
+        let filter = GalleryFilter::from_query("q=warm+glow%21&sort=oldest&utm_source=feed");
+        assert_eq!(filter.search, "warm glow!");
+        assert_eq!(filter.sort, GallerySort::Oldest);
+        assert!(filter.tags.is_empty());
+    }
+
+    #[test]
+    fn keeps_malformed_escapes() {
+        assert_eq!(decode_component("100%"), "100%");
+        assert_eq!(decode_component("%G1%4"), "%G1%4");
+        assert_eq!(decode_component("%+1"), "% 1");
+        assert_eq!(decode_component("%e6%97%a5"), "日");
+        assert_eq!(GalleryFilter::from_query("sort=bogus").sort, GallerySort::Newest);
+    }
+
+    #[test]
+    fn ignores_duplicate_and_empty_tags() {
+        let filter = GalleryFilter::from_query("tag=sdf&tag=&&tag=sdf&tag=audio&tag=sd%66");
+        assert_eq!(filter.tags, ["sdf", "audio"]);
+    }
+
+    #[test]
+    fn requires_every_selected_tag() {
+        let both = entry(Slug::AudioSpectrum, "Spectrum", "", &["audio", "sdf"]);
+        let one = entry(Slug::KeyboardLights, "Lights", "", &["audio"]);
+        let mut filter = GalleryFilter::default();
+        filter.toggle_tag("audio");
+        assert!(filter.matches(&both) && filter.matches(&one));
+        filter.toggle_tag("sdf");
+        assert!(filter.matches(&both) && !filter.matches(&one));
+        filter.toggle_tag("audio");
+        assert_eq!(filter.tags, ["sdf"]);
+    }
+
+    #[test]
+    fn search_words_ignore_case_and_span_title_and_description() {
+        let ripples = entry(Slug::PointerRipples, "Pointer Ripples", "Rings spread from every touch.", &[]);
+        let search = |text: &str| GalleryFilter { search: text.to_string(), ..GalleryFilter::default() };
+        assert!(search("").matches(&ripples));
+        assert!(search("RIPPLES").matches(&ripples));
+        assert!(search("  ripples   TOUCH ").matches(&ripples));
+        assert!(!search("ripples mouse").matches(&ripples));
+    }
+
+    #[test]
+    fn date_ties_keep_the_order_works_were_added_in() {
+        let dated = |slug, position, published_at: Option<&str>, featured| GalleryEntry {
+            position,
+            published_at: published_at.map(str::to_string),
+            featured,
+            ..entry(slug, "", "", &[])
+        };
+        let entries = [
+            dated(Slug::HelloTriangle, 0, None, false),
+            dated(Slug::GlslsandboxExample, 1, Some("2024-05-01"), false),
+            dated(Slug::AudioSpectrum, 2, Some("2024-06-01"), true),
+            dated(Slug::KeyboardLights, 3, None, false),
+            dated(Slug::PointerRipples, 4, Some("2024-05-01"), true),
+        ];
+        let sorted = |sort: GallerySort| {
+            let mut entries = entries.to_vec();
+            sort.sort(&mut entries);
+            entries.into_iter().map(|entry| entry.slug).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(GallerySort::Newest), [
+            Slug::AudioSpectrum, Slug::GlslsandboxExample, Slug::PointerRipples, Slug::HelloTriangle, Slug::KeyboardLights,
+        ]);
+        assert_eq!(sorted(GallerySort::Oldest), [
+            Slug::HelloTriangle, Slug::KeyboardLights, Slug::GlslsandboxExample, Slug::PointerRipples, Slug::AudioSpectrum,
+        ]);
+        assert_eq!(sorted(GallerySort::Featured), [
+            Slug::AudioSpectrum, Slug::PointerRipples, Slug::GlslsandboxExample, Slug::HelloTriangle, Slug::KeyboardLights,
+        ]);
+    }
+
+    #[test]
+    fn arranges_matches_in_sort_order() {
+        let entries = [
+            entry(Slug::AudioSpectrum, "Spectrum", "", &["audio"]),
+            entry(Slug::KeyboardLights, "Lights", "", &[]),
+            entry(Slug::PointerRipples, "Ripples", "", &["audio"]),
+        ];
+        let filter = GalleryFilter { tags: vec!["audio".to_string()], sort: GallerySort::Title, ..GalleryFilter::default() };
+        assert_eq!(arrange(&filter, &entries), [Slug::PointerRipples, Slug::AudioSpectrum]);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+use strum::Display;
+use zoon::*;
+use crate::theme;
+
+/// Frame cost the governor tries to hold, in seconds.
+const TARGET_FRAME_TIME: f32 = 1.0 / 60.0;
+/// Frames averaged before judging the quality level.
+const SAMPLE_FRAMES: usize = 30;
+/// Slower than the budget by this factor steps the quality down.
+const SLOW_FACTOR: f32 = 1.25;
+/// Within the budget by this factor for `recover_after` seconds steps the quality up.
+const FAST_FACTOR: f32 = 1.1;
+const MIN_RECOVER_SECONDS: f32 = 5.0;
+const MAX_RECOVER_SECONDS: f32 = 60.0;
+
+// ------ Quality ------
+
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quality {
+    Minimal,
+    Low,
+    Balanced,
+    #[default]
+    High,
+}
+
+impl Quality {
+    /// Multiplies the canvas pixel ratio.
+    pub fn render_scale(&self) -> f64 {
+        match self {
+            Quality::High => 1.0,
+            Quality::Balanced => 0.75,
+            Quality::Low | Quality::Minimal => 0.5,
+        }
+    }
+
+    pub fn fps_cap(&self) -> Option<f32> {
+        match self {
+            Quality::Minimal => Some(30.0),
+            _ => None,
+        }
+    }
+
+    fn lower(&self) -> Option<Self> {
+        match self {
+            Quality::High => Some(Quality::Balanced),
+            Quality::Balanced => Some(Quality::Low),
+            Quality::Low => Some(Quality::Minimal),
+            Quality::Minimal => None,
+        }
+    }
+
+    fn higher(&self) -> Option<Self> {
+        match self {
+            Quality::Minimal => Some(Quality::Low),
+            Quality::Low => Some(Quality::Balanced),
+            Quality::Balanced => Some(Quality::High),
+            Quality::High => None,
+        }
+    }
+}
+
+// ------ Governor ------
+
+/// Steps the quality down while frames cost more than the budget and back up once they've been steady for a while.
+/// The cost is the time spent producing a frame, not the time between frames, which vsync and the fps cap round up.
+/// Every step down doubles the wait before the next step up, so a level that can't be held isn't retried constantly.
+#[derive(Clone, Debug)]
+pub struct Governor {
+    quality: Quality,
+    frame_costs: VecDeque<f32>,
+    steady_for: f32,
+    recover_after: f32,
+}
+
+impl Default for Governor {
+    fn default() -> Self {
+        Self {
+            quality: Quality::default(),
+            frame_costs: VecDeque::with_capacity(SAMPLE_FRAMES),
+            steady_for: 0.0,
+            recover_after: MIN_RECOVER_SECONDS,
+        }
+    }
+}
+
+impl Governor {
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Minimum seconds between rendered frames.
+    pub fn frame_interval(&self) -> f32 {
+        self.quality.fps_cap().map_or(0.0, |fps| 1.0 / fps)
+    }
+
+    /// Records what a rendered frame cost on the CPU or GPU, whichever took longer, and the seconds since
+    /// the previous rendered frame. Returns the quality to render the next frame at.
+    pub fn record(&mut self, frame_cost: f32, elapsed: f32) -> Quality {
+        if self.frame_costs.len() == SAMPLE_FRAMES {
+            self.frame_costs.pop_front();
+        }
+        self.frame_costs.push_back(frame_cost);
+        if self.frame_costs.len() < SAMPLE_FRAMES {
+            return self.quality;
+        }
+
+        let average = self.frame_costs.iter().sum::<f32>() / SAMPLE_FRAMES as f32;
+        // A capped frame rate leaves more time for each frame
+        let budget = TARGET_FRAME_TIME.max(self.frame_interval());
+        if average > budget * SLOW_FACTOR {
+            if let Some(lower) = self.quality.lower() {
+                self.change_quality(lower);
+                self.recover_after = (self.recover_after * 2.0).min(MAX_RECOVER_SECONDS);
+            }
+        } else if average < budget * FAST_FACTOR {
+            self.steady_for += elapsed;
+            if self.steady_for >= self.recover_after {
+                if let Some(higher) = self.quality.higher() {
+                    self.change_quality(higher);
+                }
+            }
+        } else {
+            self.steady_for = 0.0;
+        }
+        self.quality
+    }
+
+    /// Drops samples taken before a pause, which say nothing about the current load.
+    pub fn reset_samples(&mut self) {
+        self.frame_costs.clear();
+        self.steady_for = 0.0;
+    }
+
+    fn change_quality(&mut self, quality: Quality) {
+        self.quality = quality;
+        self.reset_samples();
+    }
+}
+
+// ------ View ------
+
+pub fn quality_indicator(quality: impl Signal<Item = Quality> + Unpin + 'static) -> impl Element {
+    El::new()
+        .s(Font::new().size(14).color_signal(theme::secondary_text_color()))
+        .child_signal(quality.map(|quality| format!("Quality: {quality}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOW: f32 = TARGET_FRAME_TIME * 2.0;
+    const FAST: f32 = TARGET_FRAME_TIME * 0.5;
+    /// Between the two thresholds, neither slow nor steady.
+    const BORDERLINE: f32 = TARGET_FRAME_TIME * 1.2;
+
+    fn record_frames(governor: &mut Governor, frame_cost: f32, elapsed: f32, frames: usize) -> Quality {
+        (0..frames).fold(governor.quality(), |_, _| governor.record(frame_cost, elapsed))
+    }
+
+    #[test]
+    fn steps_down_once_per_full_sample_window() {
+        let mut governor = Governor::default();
+        assert_eq!(record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES - 1), Quality::High);
+        assert_eq!(governor.record(SLOW, SLOW), Quality::Balanced);
+        // The samples taken at the previous level don't count against the new one
+        assert_eq!(record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES - 1), Quality::Balanced);
+        assert_eq!(governor.record(SLOW, SLOW), Quality::Low);
+        assert_eq!(record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES * 4), Quality::Minimal);
+    }
+
+    #[test]
+    fn holds_the_level_between_the_thresholds() {
+        let mut governor = Governor::default();
+        record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES);
+        assert_eq!(governor.quality(), Quality::Balanced);
+        let seconds = MAX_RECOVER_SECONDS * 2.0;
+        assert_eq!(record_frames(&mut governor, BORDERLINE, 0.5, (seconds / 0.5) as usize), Quality::Balanced);
+        assert_eq!(governor.steady_for, 0.0);
+    }
+
+    #[test]
+    fn doubles_the_wait_after_a_failed_recovery() {
+        let mut governor = Governor::default();
+        // Stepping down from High already doubles the first wait to 10 seconds
+        record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES);
+        assert_eq!(record_frames(&mut governor, FAST, 0.5, SAMPLE_FRAMES - 1 + 19), Quality::Balanced);
+        assert_eq!(governor.record(FAST, 0.5), Quality::High);
+
+        // High still can't be held, so the next recovery waits 20 seconds
+        record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES);
+        assert_eq!(governor.quality(), Quality::Balanced);
+        assert_eq!(record_frames(&mut governor, FAST, 0.5, SAMPLE_FRAMES - 1 + 39), Quality::Balanced);
+        assert_eq!(governor.record(FAST, 0.5), Quality::High);
+    }
+
+    #[test]
+    fn caps_the_wait() {
+        let mut governor = Governor::default();
+        for _ in 0..10 {
+            record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES);
+            governor.quality = Quality::High;
+        }
+        assert_eq!(governor.recover_after, MAX_RECOVER_SECONDS);
+    }
+
+    #[test]
+    fn reset_drops_samples_from_before_a_pause() {
+        let mut governor = Governor::default();
+        record_frames(&mut governor, SLOW, SLOW, SAMPLE_FRAMES - 1);
+        governor.reset_samples();
+        assert_eq!(record_frames(&mut governor, FAST, 0.5, SAMPLE_FRAMES), Quality::High);
+    }
+
+    #[test]
+    fn capped_frame_rate_widens_the_budget() {
+        let mut governor = Governor { quality: Quality::Minimal, ..Governor::default() };
+        let cost = 1.0 / 40.0;
+        // Steady for the 30 fps cap of Minimal, but too slow for 60 fps at Low
+        assert_eq!(record_frames(&mut governor, cost, 0.5, SAMPLE_FRAMES - 1 + 10), Quality::Low);
+        assert_eq!(record_frames(&mut governor, cost, cost, SAMPLE_FRAMES), Quality::Minimal);
+    }
+}
@@ -0,0 +1,51 @@
+use works::KeyboardState;
+use zoon::{*, web_sys::{Event, HtmlElement, KeyboardEvent}};
+
+/// Feeds key events of a focused canvas into `state`. Listening on the canvas itself
+/// means typing anywhere else on the page never reaches the work. Removes its listeners when dropped.
+pub struct KeyboardListener {
+    target: HtmlElement,
+    on_key_down: Closure<dyn FnMut(KeyboardEvent)>,
+    on_key_up: Closure<dyn FnMut(KeyboardEvent)>,
+    on_blur: Closure<dyn FnMut(Event)>,
+}
+
+impl KeyboardListener {
+    pub fn new(target: &HtmlElement, state: Mutable<KeyboardState>) -> Self {
+        // Make the canvas focusable by click and Tab
+        target.set_tab_index(0);
+
+        let on_key_down = Closure::<dyn FnMut(KeyboardEvent)>::new(clone!((state) move |event: KeyboardEvent| {
+            // Leave shortcuts and Tab (to move focus away) to the browser
+            if event.ctrl_key() || event.meta_key() || event.alt_key() || event.key() == "Tab" {
+                return;
+            }
+            // Keep arrows and space from scrolling the page while the work has focus
+            event.prevent_default();
+            state.lock_mut().key_down(event.key_code());
+        }));
+        let on_key_up = Closure::<dyn FnMut(KeyboardEvent)>::new(clone!((state) move |event: KeyboardEvent| {
+            state.lock_mut().key_up(event.key_code());
+        }));
+        let on_blur = Closure::<dyn FnMut(Event)>::new(move |_| state.lock_mut().release_all());
+
+        target.add_event_listener_with_callback("keydown", on_key_down.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("keyup", on_key_up.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("blur", on_blur.as_ref().unchecked_ref()).unwrap_throw();
+
+        Self {
+            target: target.clone(),
+            on_key_down,
+            on_key_up,
+            on_blur,
+        }
+    }
+}
+
+impl Drop for KeyboardListener {
+    fn drop(&mut self) {
+        let _ = self.target.remove_event_listener_with_callback("keydown", self.on_key_down.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("keyup", self.on_key_up.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("blur", self.on_blur.as_ref().unchecked_ref());
+    }
+}
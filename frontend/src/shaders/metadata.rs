@@ -0,0 +1,66 @@
+use zoon::*;
+use crate::{cms::{ExternalLink, ShaderContent}, rich_text, theme};
+
+// ------ View ------
+
+/// Dates, tags and credits of a work. Rows without data are left out.
+pub fn metadata_block(content: &ShaderContent) -> impl Element {
+    let dates = [("Published", &content.published_at), ("Updated", &content.updated_at)]
+        .into_iter()
+        .filter_map(|(label, date)| Some(format!("{label} {}", date_part(date.as_deref()?))))
+        .collect::<Vec<_>>();
+
+    Column::new()
+        .s(Width::fill().max(800))
+        .s(Gap::new().y(10))
+        .s(Padding::new().top(12))
+        .s(Borders::new().top_signal(theme::border_color().map(|color| Border::new().color(color))))
+        .s(Font::new().size(14))
+        .item((!dates.is_empty()).then(|| El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child(dates.join(" · "))))
+        .item(labeled_row("Tags", &content.tags, |tag| chip(tag).unify()))
+        .item(labeled_row("Techniques", &content.techniques, |technique| El::new().child(technique).unify()))
+        .item(labeled_row("References", &content.references, |link| external_link(link).unify()))
+        .item(labeled_row("Source", &content.source_links, |link| external_link(link).unify()))
+        .item(labeled_row("License", content.license.as_slice(), |license| El::new().child(license).unify()))
+}
+
+/// `2024-05-01` of `2024-05-01T12:00:00.000Z`.
+fn date_part(timestamp: &str) -> &str {
+    timestamp.split('T').next().unwrap_or(timestamp)
+}
+
+fn labeled_row<T: Clone>(label: &str, values: &[T], value_view: impl Fn(T) -> RawElOrText) -> Option<impl Element> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(Row::new()
+        .s(Gap::new().x(8).y(6))
+        .multiline()
+        .item(El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child(label))
+        .items(values.iter().cloned().map(value_view)))
+}
+
+fn chip(text: String) -> impl Element {
+    El::new()
+        .s(Padding::new().x(8).y(2))
+        .s(RoundedCorners::all(4))
+        .s(Background::new().color_signal(theme::hovered_background_color()))
+        .child(text)
+}
+
+/// Links with unsafe URLs are shown as plain text.
+fn external_link(link: ExternalLink) -> impl Element {
+    match rich_text::safe_url(&link.url, true) {
+        Some(url) => Link::new()
+            .s(Font::new().color_signal(theme::primary_accent_color()))
+            .to(url)
+            .new_tab(NewTab::new())
+            .label(link.title)
+            .unify(),
+        None => El::new().child(link.title).unify(),
+    }
+}
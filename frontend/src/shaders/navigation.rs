@@ -0,0 +1,139 @@
+use works::Slug;
+use zoon::{*, web_sys::{HtmlElement, KeyboardEvent}};
+use crate::{i18n::{self, LANGUAGE}, theme, Route, ROUTER};
+use super::gallery::{self, GalleryFilter, CONTENTS, ENTRIES, LAST_FILTER};
+
+// ------ Neighbors ------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Neighbors {
+    pub previous: Option<Slug>,
+    pub next: Option<Slug>,
+}
+
+impl Neighbors {
+    /// Works around `slug` in `order`. None on both sides when `slug` isn't in it.
+    pub fn find(order: &[Slug], slug: Slug) -> Self {
+        let Some(index) = order.iter().position(|arranged| *arranged == slug) else {
+            return Self::default();
+        };
+        Self {
+            previous: index.checked_sub(1).map(|previous| order[previous]),
+            next: order.get(index + 1).copied(),
+        }
+    }
+}
+
+/// Neighbors in the order of the gallery the visitor came from. When its filter excludes `slug`,
+/// the whole gallery in the same sort order is used instead.
+fn neighbors_signal(slug: Slug) -> impl Signal<Item = Neighbors> {
+    map_ref! {
+        let filter = LAST_FILTER.signal_cloned(),
+        let entries = ENTRIES.signal_cloned() => {
+            let order = gallery::arrange(filter, entries);
+            match order.contains(&slug) {
+                true => Neighbors::find(&order, slug),
+                false => {
+                    let unfiltered = GalleryFilter { sort: filter.sort, ..GalleryFilter::default() };
+                    Neighbors::find(&gallery::arrange(&unfiltered, entries), slug)
+                },
+            }
+        }
+    }.dedupe()
+}
+
+fn go_to(slug: Slug) {
+    ROUTER.go(Route::ShaderPage { title: slug.to_string() }.with_language(LANGUAGE.get()));
+}
+
+// ------ Keyboard ------
+
+/// Left and right arrows open the previous and next work. Keys already handled by a focused work,
+/// pressed inside the canvas or the source, or typed into a form field are left alone.
+/// Removes its listener when dropped.
+pub struct NavigationKeys {
+    on_key_down: Closure<dyn FnMut(KeyboardEvent)>,
+}
+
+impl NavigationKeys {
+    pub fn new(neighbors: Mutable<Neighbors>) -> Self {
+        let on_key_down = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+            if event.default_prevented() || event.ctrl_key() || event.meta_key() || event.alt_key() || event.shift_key() {
+                return;
+            }
+            let target = event.target().and_then(|target| target.dyn_into::<HtmlElement>().ok());
+            let in_form_field = target.as_ref()
+                .is_some_and(|target| target.is_content_editable() || matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"));
+            // Arrows scroll the source and may steer a work
+            let in_work_or_source = target
+                .is_some_and(|target| target.closest("canvas, pre.wgsl-source").ok().flatten().is_some());
+            if in_form_field || in_work_or_source {
+                return;
+            }
+            let neighbor = match event.key().as_str() {
+                "ArrowLeft" => neighbors.get().previous,
+                "ArrowRight" => neighbors.get().next,
+                _ => None,
+            };
+            if let Some(slug) = neighbor {
+                event.prevent_default();
+                go_to(slug);
+            }
+        });
+        window().add_event_listener_with_callback("keydown", on_key_down.as_ref().unchecked_ref()).unwrap_throw();
+
+        Self { on_key_down }
+    }
+}
+
+impl Drop for NavigationKeys {
+    fn drop(&mut self) {
+        let _ = window().remove_event_listener_with_callback("keydown", self.on_key_down.as_ref().unchecked_ref());
+    }
+}
+
+// ------ View ------
+
+/// Links to the previous and next work with their thumbnails.
+pub fn work_navigation(slug: Slug) -> impl Element {
+    let neighbors: Mutable<Neighbors> = Mutable::new(Neighbors::default());
+    let neighbors_task = Task::start_droppable(clone!((neighbors) neighbors_signal(slug).for_each_sync(move |found| neighbors.set_neq(found))));
+    let navigation_keys = NavigationKeys::new(neighbors.clone());
+
+    Row::new()
+        .s(Width::fill().max(800))
+        .s(Gap::new().x(16))
+        .item_signal(neighbors.signal_ref(|neighbors| neighbors.previous).dedupe().map_some(|slug| neighbor_link(slug, Direction::Previous)))
+        .item(El::new().s(Width::fill()))
+        .item_signal(neighbors.signal_ref(|neighbors| neighbors.next).dedupe().map_some(|slug| neighbor_link(slug, Direction::Next)))
+        .after_remove(move |_| {
+            drop(neighbors_task);
+            drop(navigation_keys);
+        })
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Previous,
+    Next,
+}
+
+fn neighbor_link(slug: Slug, direction: Direction) -> impl Element {
+    let title = CONTENTS.signal_ref(move |contents| gallery::work_title(contents, slug));
+
+    Link::new()
+        .s(Width::fill().max(240))
+        .to_signal(i18n::localized_url_signal(slug.page_url()))
+        .label(Column::new()
+            .s(Gap::new().y(6))
+            .item(Image::new()
+                .s(Width::fill())
+                .url(public_url(slug.thumbnail_path()))
+                .description_signal(CONTENTS.signal_ref(move |contents| format!("Shader work: {}", gallery::work_title(contents, slug)))))
+            .item(El::new()
+                .s(Font::new().size(14).color_signal(theme::secondary_text_color()))
+                .child_signal(title.map(move |title| match direction {
+                    Direction::Previous => format!("← {title}"),
+                    Direction::Next => format!("{title} →"),
+                }))))
+}
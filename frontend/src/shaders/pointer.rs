@@ -0,0 +1,78 @@
+use works::{PointerEvent as WorkPointerEvent, PointerState};
+use zoon::{*, web_sys::{HtmlElement, PointerEvent, WheelEvent}};
+
+/// Wheel `deltaMode` line height, in pixels.
+const LINE_HEIGHT: f64 = 16.0;
+
+/// Feeds buttons, drags, touches and wheel scrolls on a canvas into `state`. Captures the pointer
+/// on press so a drag keeps reporting after leaving the canvas. Removes its listeners when dropped.
+pub struct PointerListener {
+    target: HtmlElement,
+    on_pointer_down: Closure<dyn FnMut(PointerEvent)>,
+    on_pointer_move: Closure<dyn FnMut(PointerEvent)>,
+    on_pointer_up: Closure<dyn FnMut(PointerEvent)>,
+    on_wheel: Closure<dyn FnMut(WheelEvent)>,
+}
+
+impl PointerListener {
+    pub fn new(target: &HtmlElement, state: Mutable<PointerState>) -> Self {
+        // Keep touch drags from scrolling or zooming the page
+        let _ = target.style().set_property("touch-action", "none");
+
+        let on_pointer_down = Closure::<dyn FnMut(PointerEvent)>::new(clone!((target, state) move |event: PointerEvent| {
+            let _ = target.set_pointer_capture(event.pointer_id());
+            state.lock_mut().pointer_down(work_event(&event));
+        }));
+        let on_pointer_move = Closure::<dyn FnMut(PointerEvent)>::new(clone!((state) move |event: PointerEvent| {
+            state.lock_mut().pointer_move(work_event(&event));
+        }));
+        // The capture is released by the browser on `pointerup` and `pointercancel`
+        let on_pointer_up = Closure::<dyn FnMut(PointerEvent)>::new(clone!((state) move |event: PointerEvent| {
+            state.lock_mut().pointer_up(work_event(&event));
+        }));
+        let on_wheel = Closure::<dyn FnMut(WheelEvent)>::new(move |event: WheelEvent| {
+            event.prevent_default();
+            let scale = match event.delta_mode() {
+                WheelEvent::DOM_DELTA_LINE => LINE_HEIGHT,
+                WheelEvent::DOM_DELTA_PAGE => window().inner_height().ok().and_then(|height| height.as_f64()).unwrap_or(800.0),
+                _ => 1.0,
+            };
+            state.lock_mut().wheel(((event.delta_x() * scale) as f32, (event.delta_y() * scale) as f32));
+        });
+
+        target.add_event_listener_with_callback("pointerdown", on_pointer_down.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("pointermove", on_pointer_move.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("pointerup", on_pointer_up.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("pointercancel", on_pointer_up.as_ref().unchecked_ref()).unwrap_throw();
+        target.add_event_listener_with_callback("wheel", on_wheel.as_ref().unchecked_ref()).unwrap_throw();
+
+        Self {
+            target: target.clone(),
+            on_pointer_down,
+            on_pointer_move,
+            on_pointer_up,
+            on_wheel,
+        }
+    }
+}
+
+impl Drop for PointerListener {
+    fn drop(&mut self) {
+        let _ = self.target.remove_event_listener_with_callback("pointerdown", self.on_pointer_down.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("pointermove", self.on_pointer_move.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("pointerup", self.on_pointer_up.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("pointercancel", self.on_pointer_up.as_ref().unchecked_ref());
+        let _ = self.target.remove_event_listener_with_callback("wheel", self.on_wheel.as_ref().unchecked_ref());
+    }
+}
+
+fn work_event(event: &PointerEvent) -> WorkPointerEvent {
+    WorkPointerEvent {
+        id: event.pointer_id(),
+        position: (event.offset_x() as f32, event.offset_y() as f32),
+        buttons: event.buttons() as u32,
+        pressure: event.pressure(),
+        is_primary: event.is_primary(),
+        is_touch: event.pointer_type() == "touch",
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use works::Slug;
+use zoon::*;
+use super::canvas::CanvasState;
+use super::visibility::VisibilityObserver;
+
+/// Upper bound of gallery cards playing their work at the same time.
+/// On the WebGL2 fallback each live card holds a GL context, so this also bounds those.
+const MAX_LIVE_PREVIEWS: usize = 4;
+
+// ------ Scheduler ------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Demand {
+    visible: bool,
+    hovered: bool,
+}
+
+/// Keyed by the card's position in the gallery.
+static DEMANDS: Lazy<Mutable<BTreeMap<usize, Demand>>> = lazy::default();
+static LIVE_PREVIEWS: Lazy<Mutable<Vec<usize>>> = lazy::default();
+
+fn update_demand(index: usize, f: impl FnOnce(&mut Demand)) {
+    let mut demands = DEMANDS.lock_mut();
+    f(demands.entry(index).or_default());
+    LIVE_PREVIEWS.set_neq(schedule(&demands, MAX_LIVE_PREVIEWS));
+}
+
+fn remove_demand(index: usize) {
+    let mut demands = DEMANDS.lock_mut();
+    demands.remove(&index);
+    LIVE_PREVIEWS.set_neq(schedule(&demands, MAX_LIVE_PREVIEWS));
+}
+
+/// Hovered cards come first, then visible ones in gallery order.
+fn schedule(demands: &BTreeMap<usize, Demand>, limit: usize) -> Vec<usize> {
+    let hovered = demands.iter().filter(|(_, demand)| demand.hovered);
+    let visible = demands.iter().filter(|(_, demand)| demand.visible && !demand.hovered);
+    hovered.chain(visible)
+        .map(|(index, _)| *index)
+        .take(limit)
+        .collect()
+}
+
+fn is_live_signal(index: usize) -> impl Signal<Item = bool> {
+    LIVE_PREVIEWS.signal_ref(move |live| live.contains(&index)).dedupe()
+}
+
+// ------ View ------
+
+/// Gallery card showing the static thumbnail, overlaid by the live work while the scheduler allows it.
+pub fn preview_card(index: usize, slug: Slug) -> impl Element {
+    let canvas_state = CanvasState::default();
+    let visibility_observer: Mutable<Option<VisibilityObserver>> = Mutable::new(None);
+
+    Stack::new()
+        .s(Width::fill())
+        .on_hovered_change(move |hovered| update_demand(index, |demand| demand.hovered = hovered))
+        .layer(Image::new()
+            .s(Width::fill())
+            .url(public_url(slug.thumbnail_path()))
+            .description(format!("Shader work: {}", slug.title())))
+        .layer_signal(is_live_signal(index).map_true(move || preview_canvas(slug, canvas_state.clone())))
+        .after_insert(clone!((visibility_observer) move |el| {
+            visibility_observer.set(Some(VisibilityObserver::new(&el, move |visible| {
+                update_demand(index, |demand| demand.visible = visible)
+            })));
+        }))
+        .after_remove(move |_| {
+            visibility_observer.set(None);
+            remove_demand(index);
+        })
+}
+
+fn preview_canvas(slug: Slug, canvas_state: CanvasState) -> impl Element {
+    Canvas::new()
+        .width(180)
+        .height(180)
+        .s(Width::fill())
+        .s(Height::fill())
+        .update_raw_el(clone!((canvas_state) move |el| el
+            .on_resize(clone!((canvas_state) move |w, h| canvas_state.set_size(w, h)))
+            .event_handler(clone!((canvas_state) move |event: events_extra::PointerMove| {
+                canvas_state.pointer_position.set_neq((event.offset_x(), event.offset_y()));
+            }))
+        ))
+        .after_insert(clone!((canvas_state) move |canvas| Task::start(canvas_state.run_shared(canvas, slug))))
+        .after_remove(move |_| canvas_state.stop())
+}
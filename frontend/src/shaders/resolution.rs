@@ -0,0 +1,74 @@
+use zoon::{*, web_sys::AddEventListenerOptions};
+use crate::theme;
+
+pub const RENDER_SCALES: [f64; 4] = [0.5, 1.0, 1.5, 2.0];
+const STORAGE_KEY: &str = "render-scale";
+
+// ------ Events ------
+
+#[derive(Clone, Copy)]
+struct RenderScaleSelected(f64);
+
+// ------ States ------
+
+/// Follows the window as it moves between displays or gets zoomed.
+pub static DEVICE_PIXEL_RATIO: Lazy<Mutable<f64>> = Lazy::new(|| {
+    let ratio = window().device_pixel_ratio();
+    watch_device_pixel_ratio(ratio);
+    Mutable::new(ratio)
+});
+
+/// Multiplies the device pixel ratio of every work canvas.
+pub static RENDER_SCALE: Lazy<Mutable<f64>> = Lazy::new(|| {
+    on(|RenderScaleSelected(scale)| {
+        RENDER_SCALE.set_neq(scale);
+        if let Err(error) = local_storage().insert(STORAGE_KEY, &scale) {
+            eprintln!("Failed to store render scale setting: {:?}", error);
+        }
+    });
+    let scale = match local_storage().get(STORAGE_KEY) {
+        Some(Ok(scale)) if RENDER_SCALES.contains(&scale) => scale,
+        _ => 1.0,
+    };
+    Mutable::new(scale)
+});
+
+/// A `resolution` media query only reports leaving its own ratio, so each change registers a query for the next one.
+fn watch_device_pixel_ratio(ratio: f64) {
+    let Some(query) = window().match_media(&format!("(resolution: {ratio}dppx)")).ok().flatten() else {
+        return
+    };
+    let on_change = Closure::once_into_js(|| {
+        let ratio = window().device_pixel_ratio();
+        DEVICE_PIXEL_RATIO.set_neq(ratio);
+        watch_device_pixel_ratio(ratio);
+    });
+    let mut options = AddEventListenerOptions::new();
+    options.once(true);
+    query.add_event_listener_with_callback_and_add_event_listener_options("change", on_change.unchecked_ref(), &options)
+        .unwrap_throw();
+}
+
+// ------ View ------
+
+pub fn render_scale_selector() -> impl Element {
+    Row::new()
+        .s(Gap::new().x(8))
+        .s(Font::new().size(14))
+        .item(El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child("Resolution"))
+        .items(RENDER_SCALES.map(|scale| {
+            let selected = RENDER_SCALE.signal().map(move |current| current == scale).dedupe();
+            Button::new()
+                .s(Padding::new().x(10).y(4))
+                .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+                .s(RoundedCorners::all(4))
+                .s(Background::new().color_signal(map_ref! {
+                    let selected = selected,
+                    let color = theme::hovered_background_color() => selected.then(|| color.clone())
+                }))
+                .label(format!("{scale}×"))
+                .on_press(move || emit(RenderScaleSelected(scale)))
+        }))
+}
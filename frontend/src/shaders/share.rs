@@ -0,0 +1,249 @@
+use js_sys::Reflect;
+use wasm_bindgen_futures::JsFuture;
+use works::Slug;
+use zoon::{*, web_sys::{DomException, ShareData}};
+use crate::{clipboard, i18n::LANGUAGE, theme::{self, Theme}};
+use super::{canvas::CanvasState, resolution::{RENDER_SCALE, RENDER_SCALES}};
+
+const EMBED_WIDTH: u32 = 800;
+const EMBED_HEIGHT: u32 = 450;
+
+// ------ States ------
+
+/// True when the page was opened by an embed snippet. Only the work's canvas is shown then.
+pub static EMBEDDED: Lazy<bool> = Lazy::new(|| SharedView::from_query(&window().location().search().unwrap_or_default()).embed);
+
+// ------ Shared view ------
+
+/// What a permalink restores besides the work and language (which live in the path):
+/// the time offset and the page-wide settings the work is rendered with. Works have no parameters of their own.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SharedView {
+    /// Seconds into the work.
+    pub time: f32,
+    /// Drives the work's palette.
+    pub theme: Option<Theme>,
+    /// One of `RENDER_SCALES`.
+    pub render_scale: Option<f64>,
+    pub embed: bool,
+}
+
+impl SharedView {
+    /// Reads `?t=12.5&theme=dark&scale=1.5&embed=1`, with or without the leading `?`.
+    /// Invalid values are ignored rather than rejecting the whole link.
+    pub fn from_query(query: &str) -> Self {
+        let mut view = Self::default();
+        for pair in query.trim_start_matches('?').split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "t" => view.time = value.parse::<f32>().ok().filter(|time| time.is_finite() && *time >= 0.0).unwrap_or_default(),
+                "theme" => view.theme = theme_from_code(value),
+                "scale" => view.render_scale = value.parse().ok().filter(|scale| RENDER_SCALES.contains(scale)),
+                "embed" => view.embed = value != "0",
+                _ => {},
+            }
+        }
+        view
+    }
+
+    pub fn to_query(&self) -> String {
+        let mut pairs = vec![format!("t={:.1}", self.time)];
+        if let Some(theme) = self.theme {
+            pairs.push(format!("theme={}", theme_code(theme)));
+        }
+        if let Some(scale) = self.render_scale {
+            pairs.push(format!("scale={scale}"));
+        }
+        if self.embed {
+            pairs.push("embed=1".to_string());
+        }
+        format!("?{}", pairs.join("&"))
+    }
+}
+
+fn theme_code(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Light => "light",
+        Theme::Dark => "dark",
+    }
+}
+
+fn theme_from_code(code: &str) -> Option<Theme> {
+    match code {
+        "light" => Some(Theme::Light),
+        "dark" => Some(Theme::Dark),
+        _ => None,
+    }
+}
+
+/// `path` is the localized path of the work's page.
+pub fn permalink(origin: &str, path: &str, view: &SharedView) -> String {
+    format!("{origin}{path}{}", view.to_query())
+}
+
+pub fn embed_snippet(url: &str, title: &str) -> String {
+    format!(
+        r#"<iframe src="{}" title="{}" width="{EMBED_WIDTH}" height="{EMBED_HEIGHT}" style="border: 0" allow="fullscreen" loading="lazy"></iframe>"#,
+        escape_attribute(url),
+        escape_attribute(title),
+    )
+}
+
+fn escape_attribute(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Applies a permalink's parameters to the canvas before the work starts. The theme only colors this canvas,
+/// and the render scale isn't stored, so the visitor's own settings come back on their next visit.
+pub fn restore_view(canvas_state: &CanvasState) {
+    let view = SharedView::from_query(&window().location().search().unwrap_or_default());
+    canvas_state.time.set(view.time);
+    canvas_state.theme.set_neq(view.theme);
+    if let Some(scale) = view.render_scale {
+        RENDER_SCALE.set_neq(scale);
+    }
+}
+
+fn current_view(canvas_state: &CanvasState) -> SharedView {
+    SharedView {
+        time: canvas_state.time.get(),
+        theme: Some(canvas_state.theme()),
+        render_scale: Some(RENDER_SCALE.get()),
+        embed: false,
+    }
+}
+
+fn current_permalink(slug: Slug, view: &SharedView) -> String {
+    let origin = window().location().origin().unwrap_or_default();
+    permalink(&origin, &LANGUAGE.get().localize(&slug.page_url()), view)
+}
+
+fn can_web_share() -> bool {
+    Reflect::has(&window().navigator(), &JsValue::from_str("share")).unwrap_or(false)
+}
+
+async fn web_share(title: &str, url: &str) -> Result<(), JsValue> {
+    let mut data = ShareData::new();
+    data.title(title).url(url);
+    JsFuture::from(window().navigator().share_with_data(&data)).await?;
+    Ok(())
+}
+
+// ------ View ------
+
+/// Share button opening a panel with the permalink and the embed code, taken at the moment it opened.
+pub fn share_panel(slug: Slug, canvas_state: CanvasState) -> impl Element {
+    let view: Mutable<Option<SharedView>> = Mutable::new(None);
+
+    Column::new()
+        .s(Gap::new().y(10))
+        .s(Font::new().size(14))
+        .item(Button::new()
+            .s(Padding::new().x(10).y(4))
+            .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+            .s(RoundedCorners::all(4))
+            .label_signal(view.signal_ref(Option::is_some).map_bool(|| "Close share", || "Share"))
+            .update_raw_el(clone!((view) move |el| el.attr_signal("aria-expanded", view.signal_ref(Option::is_some).map_bool(|| "true", || "false"))))
+            .on_press(clone!((view) move || {
+                let opened = view.lock_ref().is_none().then(|| current_view(&canvas_state));
+                view.set(opened);
+            })))
+        .item_signal(view.signal().map_some(move |view| share_options(slug, view)))
+}
+
+fn share_options(slug: Slug, view: SharedView) -> impl Element {
+    let url = current_permalink(slug, &view);
+    let embed_url = current_permalink(slug, &SharedView { embed: true, ..view });
+    let embed_code = embed_snippet(&embed_url, &format!("Shader work: {}", slug.title()));
+
+    Column::new()
+        .s(Width::fill().max(800))
+        .s(Gap::new().y(10))
+        .s(Padding::all(12))
+        .s(RoundedCorners::all(4))
+        .s(Background::new().color_signal(theme::secondary_background_color()))
+        .item(share_row("Link", url.clone(), clipboard::copy_button("Copy link", clone!((url) move || url.clone()))))
+        .item(share_row("Embed", embed_code.clone(), clipboard::copy_button("Copy code", move || embed_code.clone())))
+        .item(can_web_share().then(|| Button::new()
+            .s(Padding::new().x(10).y(4))
+            .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+            .s(RoundedCorners::all(4))
+            .label("Share via…")
+            .on_press(move || {
+                let url = url.clone();
+                Task::start(async move {
+                    // Also rejected with `AbortError` when the visitor dismisses the share sheet
+                    if let Err(error) = web_share(&slug.title(), &url).await {
+                        let aborted = error.dyn_ref::<DomException>().is_some_and(|error| error.name() == "AbortError");
+                        if !aborted {
+                            eprintln!("Failed to share: {:?}", error);
+                        }
+                    }
+                });
+            })))
+}
+
+fn share_row(label: &str, text: String, copy_button: impl Element) -> impl Element {
+    Column::new()
+        .s(Gap::new().y(6))
+        .item(El::new()
+            .s(Font::new().color_signal(theme::secondary_text_color()))
+            .child(label))
+        .item(Row::new()
+            .s(Gap::new().x(8))
+            .item(El::new()
+                .s(Width::fill())
+                .s(Padding::new().x(8).y(4))
+                .s(Font::new().family([FontFamily::Monospace]).size(12))
+                .s(Background::new().color_signal(theme::primary_background_color()))
+                .update_raw_el(|el| el
+                    .style("overflow-wrap", "anywhere")
+                    .style("user-select", "all"))
+                .child(text))
+            .item(copy_button))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_round_trip() {
+        let view = SharedView { time: 12.5, theme: Some(Theme::Light), render_scale: Some(1.5), embed: true };
+        let query = view.to_query();
+        assert_eq!(query, "?t=12.5&theme=light&scale=1.5&embed=1");
+        assert_eq!(SharedView::from_query(&query), view);
+        assert_eq!(SharedView::from_query(query.trim_start_matches('?')), view);
+
+        let plain = SharedView { time: 3.0, ..SharedView::default() };
+        assert_eq!(plain.to_query(), "?t=3.0");
+        assert_eq!(SharedView::from_query(&plain.to_query()), plain);
+    }
+
+    #[test]
+    fn ignores_invalid_values() {
+        let view = SharedView::from_query("?t=-4&theme=sepia&scale=3&embed=0&utm_source=feed");
+        assert_eq!(view, SharedView::default());
+        assert_eq!(SharedView::from_query("t=NaN&scale=abc").time, 0.0);
+        assert_eq!(SharedView::from_query("t=inf").time, 0.0);
+        assert_eq!(SharedView::from_query(""), SharedView::default());
+    }
+
+    #[test]
+    fn escapes_attribute_values() {
+        assert_eq!(escape_attribute("plain"), "plain");
+        assert_eq!(
+            escape_attribute(r#"https://example.com/?t=1&theme=dark" onload="x<y>"#),
+            "https://example.com/?t=1&amp;theme=dark&quot; onload=&quot;x&lt;y&gt;",
+        );
+        // Existing entities are escaped again rather than passed through
+        assert_eq!(escape_attribute("&amp;"), "&amp;amp;");
+    }
+
+    #[test]
+    fn embed_snippet_escapes_url_and_title() {
+        let snippet = embed_snippet("https://example.com/en/shaders/x?t=1.0&embed=1", r#"Say "hi""#);
+        assert!(snippet.contains(r#"src="https://example.com/en/shaders/x?t=1.0&amp;embed=1""#));
+        assert!(snippet.contains(r#"title="Say &quot;hi&quot;""#));
+    }
+}
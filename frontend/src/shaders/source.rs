@@ -0,0 +1,293 @@
+use works::Slug;
+use zoon::*;
+use crate::{clipboard, theme};
+
+// ------ Highlighting ------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Highlight {
+    Plain,
+    Keyword,
+    Type,
+    Attribute,
+    Number,
+    Comment,
+}
+
+impl Highlight {
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            Highlight::Plain => None,
+            Highlight::Keyword => Some("wgsl-keyword"),
+            Highlight::Type => Some("wgsl-type"),
+            Highlight::Attribute => Some("wgsl-attribute"),
+            Highlight::Number => Some("wgsl-number"),
+            Highlight::Comment => Some("wgsl-comment"),
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "alias", "break", "case", "const", "const_assert", "continue", "continuing", "default", "diagnostic",
+    "discard", "else", "enable", "false", "fn", "for", "if", "let", "loop", "override", "requires", "return",
+    "struct", "switch", "true", "var", "while",
+];
+
+const TYPES: &[&str] = &["array", "atomic", "bool", "f16", "f32", "i32", "ptr", "sampler", "sampler_comparison", "u32"];
+
+/// Built-in vector, matrix and texture types, predeclared aliases like `vec3f` included.
+/// Capitalized names are taken to be user structs.
+fn is_type(word: &str) -> bool {
+    let is_dimension = |c: char| ('2'..='4').contains(&c);
+    let has_suffix = |rest: &str| rest.is_empty() || matches!(rest, "f" | "h" | "i" | "u");
+    let is_vector = word.strip_prefix("vec")
+        .is_some_and(|rest| rest.starts_with(is_dimension) && has_suffix(&rest[1..]));
+    let is_matrix = word.strip_prefix("mat")
+        .is_some_and(|rest| {
+            let mut chars = rest.chars();
+            matches!((chars.next(), chars.next(), chars.next()), (Some(c), Some('x'), Some(r)) if is_dimension(c) && is_dimension(r))
+                && matches!(chars.as_str(), "" | "f" | "h")
+        });
+    TYPES.contains(&word) || is_vector || is_matrix || word.starts_with("texture_") || word.starts_with(char::is_uppercase)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Length of a block comment at the start of `text`. WGSL block comments nest.
+fn block_comment_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index..].starts_with(b"/*") {
+            depth += 1;
+            index += 2;
+        } else if bytes[index..].starts_with(b"*/") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return index;
+            }
+        } else {
+            index += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Covers hex, exponents and suffixes like `0x1F`, `1.5e-3` and `2u`.
+fn number_len(text: &str) -> usize {
+    let is_hex = text.starts_with("0x") || text.starts_with("0X");
+    let mut previous = ' ';
+    text.char_indices()
+        .find(|&(_, c)| {
+            let is_exponent_sign = matches!(c, '+' | '-') && matches!(previous, 'e' | 'E') && !is_hex;
+            previous = c;
+            !(c.is_ascii_alphanumeric() || c == '.' || is_exponent_sign)
+        })
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn next_token(text: &str) -> (Highlight, usize) {
+    if text.starts_with("//") {
+        return (Highlight::Comment, text.find('\n').unwrap_or(text.len()));
+    }
+    if text.starts_with("/*") {
+        return (Highlight::Comment, block_comment_len(text));
+    }
+    let mut chars = text.chars();
+    let first = chars.next().unwrap_or_default();
+    let identifier_len = |text: &str| text.find(|c| !is_identifier_char(c)).unwrap_or(text.len());
+    if first == '@' {
+        return (Highlight::Attribute, 1 + identifier_len(&text[1..]));
+    }
+    if first.is_ascii_digit() || (first == '.' && chars.next().is_some_and(|c| c.is_ascii_digit())) {
+        return (Highlight::Number, number_len(text));
+    }
+    if is_identifier_char(first) {
+        let len = identifier_len(text);
+        let word = &text[..len];
+        let highlight = if KEYWORDS.contains(&word) {
+            Highlight::Keyword
+        } else if is_type(word) {
+            Highlight::Type
+        } else {
+            Highlight::Plain
+        };
+        return (highlight, len);
+    }
+    (Highlight::Plain, first.len_utf8())
+}
+
+/// Splits WGSL into highlighted tokens, merging neighboring plain ones. Concatenated, they give back `source`.
+pub fn tokenize(source: &str) -> Vec<(Highlight, &str)> {
+    let mut tokens: Vec<(Highlight, &str)> = Vec::new();
+    let mut start = 0;
+    while start < source.len() {
+        let (highlight, len) = next_token(&source[start..]);
+        let end = start + len;
+        match tokens.last_mut() {
+            Some((Highlight::Plain, text)) if highlight == Highlight::Plain => *text = &source[start - text.len()..end],
+            _ => tokens.push((highlight, &source[start..end])),
+        }
+        start = end;
+    }
+    tokens
+}
+
+/// Tokens per line, with tokens spanning lines (block comments) cut at the line breaks.
+pub fn highlight_lines(source: &str) -> Vec<Vec<(Highlight, &str)>> {
+    let mut lines = vec![Vec::new()];
+    for (highlight, text) in tokenize(source) {
+        for (index, part) in text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap_throw().push((highlight, part));
+            }
+        }
+    }
+    // A trailing line break doesn't start another numbered line
+    if source.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
+// ------ View ------
+
+/// Collapsible view of the work's WGSL. Highlighted only once opened.
+pub fn source_section(slug: Slug) -> impl Element {
+    let expanded = Mutable::new(false);
+
+    run_once!(|| {
+        global_styles()
+            .style_group(StyleGroup::new(".wgsl-source")
+                .style("counter-reset", "line")
+                .style_signal("background-color", theme::secondary_background_color()))
+            .style_group(StyleGroup::new(".wgsl-line::before")
+                .style("counter-increment", "line")
+                .style("content", "counter(line)")
+                .style("display", "inline-block")
+                .style("width", "3em")
+                .style("margin-right", "1.5em")
+                .style("text-align", "right")
+                .style("user-select", "none")
+                .style_signal("color", theme::secondary_text_color()))
+            .style_group(StyleGroup::new(".wgsl-keyword").style_signal("color", theme::syntax_keyword_color()))
+            .style_group(StyleGroup::new(".wgsl-type").style_signal("color", theme::syntax_type_color()))
+            .style_group(StyleGroup::new(".wgsl-attribute").style_signal("color", theme::syntax_attribute_color()))
+            .style_group(StyleGroup::new(".wgsl-number").style_signal("color", theme::syntax_number_color()))
+            .style_group(StyleGroup::new(".wgsl-comment").style_signal("color", theme::syntax_comment_color()));
+    });
+
+    Column::new()
+        .s(Width::fill().max(800))
+        .s(Gap::new().y(8))
+        .item(Row::new()
+            .s(Gap::new().x(12))
+            .item(Button::new()
+                .s(Font::new().weight(FontWeight::Bold))
+                .label_signal(expanded.signal().map_bool(|| "▾ Source", || "▸ Source"))
+                .update_raw_el(clone!((expanded) move |el| el.attr_signal("aria-expanded", expanded.signal().map_bool(|| "true", || "false"))))
+                .on_press(clone!((expanded) move || expanded.update(|expanded| !expanded))))
+            .item_signal(expanded.signal().map_true(move || clipboard::copy_button("Copy", move || slug.source()))))
+        .item_signal(expanded.signal().map_true(move || source_view(&slug.source())))
+}
+
+fn source_view(source: &str) -> impl Element {
+    RawHtmlEl::new("pre")
+        .class("wgsl-source")
+        .style("margin", "0")
+        .style("padding", "12px 12px 12px 0")
+        .style("overflow-x", "auto")
+        .style("border-radius", "4px")
+        .style("font-size", "13px")
+        .style("line-height", "1.5")
+        .child(RawHtmlEl::new("code")
+            .style("font-family", "monospace")
+            .children(highlight_lines(source).into_iter().map(|line| {
+                RawHtmlEl::new("span")
+                    .class("wgsl-line")
+                    .children(line.into_iter().map(|(highlight, text)| {
+                        let token = RawHtmlEl::new("span").child(text.to_string());
+                        match highlight.class() {
+                            Some(class) => token.class(class),
+                            None => token,
+                        }
+                    }))
+                    .child("\n")
+            })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighted(source: &str) -> Vec<(Highlight, &str)> {
+        let tokens = tokenize(source);
+        assert_eq!(tokens.iter().map(|(_, text)| *text).collect::<String>(), source);
+        tokens.into_iter().filter(|(highlight, _)| *highlight != Highlight::Plain).collect()
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        let source = "/* outer /* inner */ still outer */ fn";
+        assert_eq!(highlighted(source), [
+            (Highlight::Comment, "/* outer /* inner */ still outer */"),
+            (Highlight::Keyword, "fn"),
+        ]);
+        assert_eq!(block_comment_len("/* a */ /* b */"), 7);
+    }
+
+    #[test]
+    fn unterminated_comments_run_to_the_end() {
+        assert_eq!(highlighted("let a /* /* */ é"), [(Highlight::Keyword, "let"), (Highlight::Comment, "/* /* */ é")]);
+        assert_eq!(highlighted("/*"), [(Highlight::Comment, "/*")]);
+        assert_eq!(highlighted("/*/"), [(Highlight::Comment, "/*/")]);
+        assert_eq!(highlighted("// no line break"), [(Highlight::Comment, "// no line break")]);
+        assert_eq!(block_comment_len("/* ü"), "/* ü".len());
+    }
+
+    #[test]
+    fn numbers_keep_prefixes_exponents_and_suffixes() {
+        assert_eq!(highlighted("0x1Fu + 1e-3f - 2u*.5h"), [
+            (Highlight::Number, "0x1Fu"),
+            (Highlight::Number, "1e-3f"),
+            (Highlight::Number, "2u"),
+            (Highlight::Number, ".5h"),
+        ]);
+        // A minus after a hex digit `e` is subtraction, not an exponent
+        assert_eq!(number_len("0x1e-3"), 4);
+        assert_eq!(number_len("1.5e+2)"), 6);
+        assert_eq!(number_len("7"), 1);
+    }
+
+    #[test]
+    fn recognizes_builtin_and_user_types() {
+        for word in ["f32", "vec3f", "vec4", "mat4x4f", "mat2x3", "texture_2d", "array", "Uniform"] {
+            assert!(is_type(word), "{word}");
+        }
+        for word in ["vec", "vec5f", "vec3x", "mat4", "mat4x5", "mat4x4i", "position"] {
+            assert!(!is_type(word), "{word}");
+        }
+        assert_eq!(highlighted("@vertex fn vs_main() -> vec4f"), [
+            (Highlight::Attribute, "@vertex"),
+            (Highlight::Keyword, "fn"),
+            (Highlight::Type, "vec4f"),
+        ]);
+    }
+
+    #[test]
+    fn splits_multiline_comments_into_lines() {
+        let lines = highlight_lines("a /* b\nc */ d\n");
+        assert_eq!(lines, [
+            vec![(Highlight::Plain, "a "), (Highlight::Comment, "/* b")],
+            vec![(Highlight::Comment, "c */"), (Highlight::Plain, " d")],
+        ]);
+        assert_eq!(highlight_lines("").len(), 1);
+    }
+}
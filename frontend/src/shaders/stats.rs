@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use zoon::*;
+use crate::theme;
+use super::canvas::CanvasState;
+
+/// Frames kept for the FPS average and the graph.
+const HISTORY: usize = 120;
+const GRAPH_WIDTH: u32 = 120;
+const GRAPH_HEIGHT: u32 = 32;
+/// Frame time at the top of the graph, in milliseconds.
+const GRAPH_MAX_FRAME_TIME: f32 = 50.0;
+const STORAGE_KEY: &str = "timing-overlay";
+
+// ------ Events ------
+
+#[derive(Clone, Copy)]
+struct ToggleTimingOverlayClicked;
+
+// ------ States ------
+
+pub static TIMING_OVERLAY: Lazy<Mutable<bool>> = Lazy::new(|| {
+    on(|ToggleTimingOverlayClicked| {
+        let enabled = !TIMING_OVERLAY.get();
+        TIMING_OVERLAY.set(enabled);
+        if let Err(error) = local_storage().insert(STORAGE_KEY, &enabled) {
+            eprintln!("Failed to store timing overlay setting: {:?}", error);
+        }
+    });
+    let enabled = match local_storage().get(STORAGE_KEY) {
+        Some(Ok(enabled)) => enabled,
+        _ => false,
+    };
+    Mutable::new(enabled)
+});
+
+/// Timings of recent frames, in milliseconds. Only recorded while the overlay is shown.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    frame_times: VecDeque<f32>,
+    pub cpu_time: f32,
+    pub gpu_time: Option<f32>,
+}
+
+impl FrameStats {
+    pub fn record(&mut self, frame_time: f32, cpu_time: f32, gpu_time: Option<f32>) {
+        if self.frame_times.len() == HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.cpu_time = cpu_time;
+        self.gpu_time = gpu_time;
+    }
+
+    pub fn fps(&self) -> f32 {
+        let total = self.frame_times.iter().sum::<f32>();
+        match total > 0.0 {
+            true => self.frame_times.len() as f32 * 1000.0 / total,
+            false => 0.0,
+        }
+    }
+
+    /// SVG `points` of the frame-time graph, newest frame on the right.
+    fn graph_points(&self) -> String {
+        let offset = HISTORY - self.frame_times.len();
+        self.frame_times.iter()
+            .enumerate()
+            .map(|(index, frame_time)| {
+                let x = (offset + index) as f32 * GRAPH_WIDTH as f32 / (HISTORY - 1) as f32;
+                let y = GRAPH_HEIGHT as f32 * (1.0 - (frame_time / GRAPH_MAX_FRAME_TIME).min(1.0));
+                format!("{x:.1},{y:.1}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// ------ View ------
+
+pub fn timing_overlay_toggle() -> impl Element {
+    Button::new()
+        .s(Padding::new().x(10).y(4))
+        .s(Font::new().size(14))
+        .s(Borders::all_signal(theme::border_color().map(|color| Border::new().color(color))))
+        .s(RoundedCorners::all(4))
+        .label_signal(TIMING_OVERLAY.signal().map_bool(|| "Hide stats", || "Show stats"))
+        .on_press(|| emit(ToggleTimingOverlayClicked))
+}
+
+pub fn timing_overlay(canvas_state: CanvasState) -> impl Element {
+    let stats = canvas_state.stats.clone();
+    let line = move |f: fn(&FrameStats) -> String| El::new().child_signal(stats.signal_ref(f));
+
+    Column::new()
+        .s(Align::new().top().left())
+        .s(Padding::all(8))
+        .s(Gap::new().y(2))
+        .s(Font::new().size(12).family([FontFamily::Monospace]).color("white"))
+        .s(Background::new().color("rgba(0, 0, 0, 0.6)"))
+        .update_raw_el(|el| el.style("pointer-events", "none"))
+        .item(line(|stats| format!("{:.0} FPS", stats.fps())))
+        .item(line(|stats| format!("CPU {:.2} ms", stats.cpu_time)))
+        .item(line(|stats| match stats.gpu_time {
+            Some(gpu_time) => format!("GPU {:.2} ms", gpu_time),
+            None => "GPU n/a".to_string(),
+        }))
+        .item(El::new().child_signal(canvas_state.adapter.signal_cloned()))
+        .item(RawSvgEl::new("svg")
+            .attr("width", &GRAPH_WIDTH.to_string())
+            .attr("height", &GRAPH_HEIGHT.to_string())
+            .child(RawSvgEl::new("polyline")
+                .attr("fill", "none")
+                .attr("stroke", "currentColor")
+                .attr("stroke-width", "1")
+                .attr_signal("points", canvas_state.stats.signal_ref(FrameStats::graph_points))))
+}
@@ -0,0 +1,45 @@
+use zoon::{*, web_sys::{IntersectionObserver, IntersectionObserverEntry}};
+
+// ------ Page ------
+
+/// False while the tab is in the background or the window is minimized.
+pub static PAGE_VISIBLE: Lazy<Mutable<bool>> = Lazy::new(|| {
+    let on_change = Closure::<dyn FnMut()>::new(|| PAGE_VISIBLE.set_neq(!document().hidden()));
+    document().add_event_listener_with_callback("visibilitychange", on_change.as_ref().unchecked_ref())
+        .unwrap_throw();
+    // Lives as long as the page
+    on_change.forget();
+    Mutable::new(!document().hidden())
+});
+
+// ------ Element ------
+
+/// Reports whether an element intersects the viewport. Disconnects when dropped.
+pub struct VisibilityObserver {
+    observer: IntersectionObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl VisibilityObserver {
+    pub fn new(element: &web_sys::Element, mut on_change: impl FnMut(bool) + 'static) -> Self {
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+            if let Some(entry) = entries.iter().last() {
+                on_change(entry.unchecked_into::<IntersectionObserverEntry>().is_intersecting());
+            }
+        });
+        let observer = IntersectionObserver::new(callback.as_ref().unchecked_ref())
+            .expect_throw("Failed to create IntersectionObserver");
+        observer.observe(element);
+
+        Self {
+            observer,
+            _callback: callback,
+        }
+    }
+}
+
+impl Drop for VisibilityObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
@@ -11,7 +11,7 @@ pub enum Theme {
 }
 
 impl Theme {
-    fn flavor(&self) -> &Flavor {
+    pub fn flavor(&self) -> &Flavor {
         match self {
             Theme::Light => &PALETTE.latte,
             Theme::Dark => &PALETTE.mocha,
@@ -110,3 +110,8 @@ assign_color!(border_color => Overlay0);
 assign_color!(primary_background_color => Base);
 assign_color!(secondary_background_color => Crust);
 assign_color!(hovered_background_color => Surface0);
+assign_color!(syntax_keyword_color => Mauve);
+assign_color!(syntax_type_color => Yellow);
+assign_color!(syntax_attribute_color => Sapphire);
+assign_color!(syntax_number_color => Peach);
+assign_color!(syntax_comment_color => Overlay1);
@@ -0,0 +1,207 @@
+use zoon::*;
+use crate::{clock, rich_text::{self, RichNode}, theme};
+
+/// Delay between animation ticks. Several characters are revealed per tick at high speeds.
+const TICK_MS: u32 = 10;
+
+// ------ States ------
+
+/// Follows the system `prefers-reduced-motion` setting.
+pub static REDUCED_MOTION: Lazy<Mutable<bool>> = Lazy::new(|| {
+    let Some(query) = window().match_media("(prefers-reduced-motion: reduce)").ok().flatten() else {
+        return Mutable::new(false)
+    };
+    let on_change = Closure::<dyn FnMut()>::new(clone!((query) move || REDUCED_MOTION.set_neq(query.matches())));
+    query.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref()).unwrap_throw();
+    // Lives as long as the page
+    on_change.forget();
+    Mutable::new(query.matches())
+});
+
+/// Progress of typing out `len` characters. Driven by elapsed time, so a late timer catches up instead of slowing down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Typewriter {
+    len: usize,
+    chars_per_second: f64,
+    /// Characters typed so far, fractional between ticks.
+    progress: f64,
+}
+
+impl Typewriter {
+    /// Starts finished when `instant` or when the speed isn't positive.
+    pub fn new(len: usize, chars_per_second: f64, instant: bool) -> Self {
+        let progress = match instant || chars_per_second <= 0.0 {
+            true => len as f64,
+            false => 0.0,
+        };
+        Self { len, chars_per_second, progress }
+    }
+
+    pub fn tick(&mut self, elapsed_ms: f64) {
+        self.progress = (self.progress + elapsed_ms.max(0.0) * self.chars_per_second / 1000.0).min(self.len as f64);
+    }
+
+    /// Types `len` new characters from the start, at the same speed.
+    pub fn restart(&mut self, len: usize, instant: bool) {
+        *self = Self::new(len, self.chars_per_second, instant);
+    }
+
+    pub fn skip(&mut self) {
+        self.progress = self.len as f64;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.progress >= self.len as f64
+    }
+
+    /// Characters to show. Everything once done, trailing empty elements included.
+    pub fn revealed(&self) -> usize {
+        match self.is_done() {
+            true => usize::MAX,
+            false => self.progress as usize,
+        }
+    }
+}
+
+fn update(typewriter: &Mutable<Typewriter>, revealed: &Mutable<usize>, f: impl FnOnce(&mut Typewriter)) {
+    let mut typewriter = typewriter.lock_mut();
+    f(&mut typewriter);
+    revealed.set_neq(typewriter.revealed());
+}
+
+async fn type_out(typewriter: Mutable<Typewriter>, revealed: Mutable<usize>) {
+    let clock = clock::clock();
+    let mut last = clock();
+    while !typewriter.lock_ref().is_done() {
+        Timer::sleep(TICK_MS).await;
+        let now = clock();
+        update(&typewriter, &revealed, |typewriter| typewriter.tick(now - last));
+        last = now;
+    }
+}
+
+// ------ View ------
+
+/// Types `content` out, restarting whenever it changes. A click or Enter on the text shows all of it at once,
+/// and it appears instantly under `prefers-reduced-motion`. Screen readers get the finished text only.
+pub fn typing_text(content: impl Signal<Item = Vec<RichNode>> + Unpin + 'static, chars_per_second: f64) -> impl Element {
+    let nodes: Mutable<Vec<RichNode>> = Mutable::new(Vec::new());
+    let typewriter = Mutable::new(Typewriter::new(0, chars_per_second, true));
+    let revealed = Mutable::new(usize::MAX);
+    // Replacing the handle aborts the animation in progress
+    let typing: Mutable<Option<TaskHandle>> = Mutable::new(None);
+    let is_typing = clone!((typewriter) move || typewriter.signal_ref(|typewriter| !typewriter.is_done()).dedupe());
+    let skip = clone!((typewriter, revealed) move || update(&typewriter, &revealed, Typewriter::skip));
+
+    let content_task = Task::start_droppable(content.for_each_sync(clone!((nodes, typewriter, revealed, typing) move |content| {
+        let len = rich_text::text_len(&content);
+        update(&typewriter, &revealed, |typewriter| typewriter.restart(len, REDUCED_MOTION.get()));
+        nodes.set(content);
+        let animate = !typewriter.lock_ref().is_done();
+        typing.set(animate.then(|| Task::start_droppable(type_out(typewriter.clone(), revealed.clone()))));
+    })));
+
+    let blink_oscillator = Oscillator::new(Duration::seconds(1));
+    blink_oscillator.cycle_wrap();
+    // Color of the Enter hint, alternating with the background
+    let blink_color = Mutable::new(String::new());
+    let blink_task = Task::start_droppable(clone!((blink_color) map_ref! {
+        let theme = theme::THEME.signal(),
+        let oscillator = blink_oscillator.signal() => {
+            match theme {
+                theme::Theme::Light => if *oscillator > 0.5 { theme::light_primary_text_color() } else { theme::light_primary_background_color() },
+                theme::Theme::Dark => if *oscillator > 0.5 { theme::dark_primary_text_color() } else { theme::dark_primary_background_color() },
+            }
+        }
+    }.for_each_sync(move |color| blink_color.set_neq(color))));
+
+    Paragraph::new()
+        .content(El::new()
+            .child_signal(nodes.signal_cloned().map(move |nodes| {
+                RawHtmlEl::new("div").children(rich_text::rich_text(&nodes, &revealed))
+            }))
+            .on_click(skip.clone())
+            .update_raw_el(|el| el
+                // Announced once typing settles, rather than character by character
+                .attr("aria-live", "polite")
+                .attr_signal("aria-busy", is_typing().map_bool(|| "true", || "false"))
+                // Focusable only while there's something to skip
+                .attr_signal("tabindex", is_typing().map_true(|| "0"))
+                .event_handler(move |event: events::KeyDown| {
+                    if event.key() == "Enter" {
+                        skip();
+                    }
+                })))
+        .content(El::new()
+            .s(Height::exact(16))
+            .s(Background::new().color_signal(theme::primary_text_color()))
+            .update_raw_el(|el| el
+                .attr("aria-hidden", "true")
+                .inner_markup_signal(is_typing().map_bool(|| "//", || ""))))
+        .content(El::new()
+            .s(Height::exact(40))
+            .s(Borders::all_signal(blink_color.signal_cloned().map(|color| Border::new().color(color))))
+            .s(RoundedCorners::all(2))
+            .s(Font::new().size(12).color_signal(blink_color.signal_cloned()))
+            .update_raw_el(|el| el
+                .attr("aria-hidden", "true")
+                .inner_markup_signal(is_typing().map_bool(|| "", || " ⏎ "))))
+        .after_remove(move |_| {
+            drop(content_task);
+            drop(typing);
+            drop(blink_task);
+            drop(blink_oscillator);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advances_by_elapsed_time() {
+        let mut typewriter = Typewriter::new(10, 100.0, false);
+        assert_eq!(typewriter.revealed(), 0);
+        typewriter.tick(25.0);
+        assert_eq!(typewriter.revealed(), 2);
+        // A late tick catches up rather than revealing a single character
+        typewriter.tick(50.0);
+        assert_eq!(typewriter.revealed(), 7);
+        typewriter.tick(-10.0);
+        assert_eq!(typewriter.revealed(), 7);
+        assert!(!typewriter.is_done());
+        typewriter.tick(1000.0);
+        assert!(typewriter.is_done());
+        assert_eq!(typewriter.revealed(), usize::MAX);
+    }
+
+    #[test]
+    fn skip_reveals_everything() {
+        let mut typewriter = Typewriter::new(10, 100.0, false);
+        typewriter.tick(10.0);
+        typewriter.skip();
+        assert!(typewriter.is_done());
+        assert_eq!(typewriter.revealed(), usize::MAX);
+    }
+
+    #[test]
+    fn reduced_motion_shows_everything_at_once() {
+        let typewriter = Typewriter::new(10, 100.0, true);
+        assert!(typewriter.is_done());
+        assert_eq!(typewriter.revealed(), usize::MAX);
+        assert!(Typewriter::new(10, 0.0, false).is_done());
+    }
+
+    #[test]
+    fn restarts_from_the_beginning_for_new_text() {
+        let mut typewriter = Typewriter::new(10, 100.0, false);
+        typewriter.skip();
+        typewriter.restart(20, false);
+        assert!(!typewriter.is_done());
+        assert_eq!(typewriter.revealed(), 0);
+        typewriter.tick(100.0);
+        assert_eq!(typewriter.revealed(), 10);
+        typewriter.restart(5, true);
+        assert!(typewriter.is_done());
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use strum::IntoEnumIterator;
+use wgpu::*;
+use works::{FrameInput, GpuContext, Palette, PointerEvent, PointerState, Slug};
+
+/// Renders shader works without a browser.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Renders a single frame of a work to a PNG file.
+    Render {
+        /// Work slug, e.g. `glslsandbox-example`.
+        slug: String,
+        /// Time in seconds.
+        #[arg(short, long, default_value_t = 0.0)]
+        time: f32,
+        #[arg(long, default_value_t = 1280)]
+        width: u32,
+        #[arg(long, default_value_t = 720)]
+        height: u32,
+        /// Uses the light theme palette instead of the dark one.
+        #[arg(long)]
+        light: bool,
+        /// Defaults to `<slug>.png`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Renders gallery thumbnails for every work (or only the given ones) at their `THUMBNAIL_TIME`.
+    Thumbnails {
+        slugs: Vec<String>,
+        /// Edge length of the square thumbnail.
+        #[arg(long, default_value_t = 720)]
+        size: u32,
+        #[arg(long, default_value_os_t = default_public_dir())]
+        public_dir: PathBuf,
+    },
+    /// Lists registered slugs.
+    List,
+}
+
+fn default_public_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../public")
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Render { slug, time, width, height, light, output } => {
+            let slug = Slug::from_str(&slug).map_err(|_| anyhow!("Unknown slug: {slug}"))?;
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{slug}.png")));
+            let mut ctx = pollster::block_on(GpuContext::headless((width, height), &slug.requirements()))?;
+            if light {
+                ctx.set_palette(Palette::new(&catppuccin::PALETTE.latte));
+            }
+            let pixels = render(&ctx, slug, time)?;
+            image::save_buffer(&output, &pixels, width, height, image::ExtendedColorType::Rgba8)?;
+            println!("{}", output.display());
+        },
+        Command::Thumbnails { slugs, size, public_dir } => {
+            let slugs = match slugs.is_empty() {
+                true => Slug::iter().collect(),
+                false => slugs.iter()
+                    .map(|slug| Slug::from_str(slug).map_err(|_| anyhow!("Unknown slug: {slug}")))
+                    .collect::<Result<Vec<_>>>()?,
+            };
+            for slug in slugs {
+                // A device per work, created with what that work requires
+                let ctx = pollster::block_on(GpuContext::headless((size, size), &slug.requirements()))?;
+                let output = public_dir.join(slug.thumbnail_path());
+                let pixels = render(&ctx, slug, slug.thumbnail_time())?;
+                image::save_buffer(&output, &pixels, size, size, image::ExtendedColorType::Rgba8)?;
+                println!("{}", output.display());
+            }
+        },
+        Command::List => {
+            for slug in Slug::iter() {
+                println!("{slug}");
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Renders `slug` at `time` seconds and returns tightly packed RGBA8 pixels.
+fn render(ctx: &GpuContext, slug: Slug, time: f32) -> Result<Vec<u8>> {
+    let size = ctx.size;
+    let mut work = slug.create(ctx);
+    let target = ctx.device.create_texture(&TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: size.0,
+            height: size.1,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: ctx.config.format,
+        usage: ctx.config.usage,
+        view_formats: &[],
+    });
+
+    // Pointer rests at the center, as if the visitor hovered the middle of the canvas
+    let center = (size.0 as f32 / 2.0, size.1 as f32 / 2.0);
+    let input = FrameInput {
+        time,
+        delta: 0.0,
+        frame: (time * 60.0) as i32,
+        pointer: center,
+    };
+    let mut pointer = PointerState::default();
+    pointer.pointer_move(PointerEvent { id: 1, position: center, buttons: 0, pressure: 0.0, is_primary: true, is_touch: false });
+    ctx.pointer.update(&ctx.queue, &mut pointer, 1.0);
+    work.render(ctx, &target, &input);
+
+    read_texture(ctx, &target)
+}
+
+fn read_texture(ctx: &GpuContext, texture: &Texture) -> Result<Vec<u8>> {
+    let (width, height) = ctx.size;
+    let unpadded_bytes_per_row = width * 4;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = ctx.device.create_buffer(&BufferDescriptor {
+        label: None,
+        size: (padded_bytes_per_row * height) as BufferAddress,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+        label: None,
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    ctx.queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    ctx.device.poll(Maintain::Wait);
+    receiver.recv()?.map_err(|error| anyhow!("Failed to map readback buffer: {error}"))?;
+
+    let padded = slice.get_mapped_range();
+    let pixels = padded
+        .chunks(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..unpadded_bytes_per_row as usize])
+        .copied()
+        .collect();
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::PathBuf;
+    use strum::IntoEnumIterator;
+    use works::Slug;
+    use super::default_public_dir;
+
+    #[test]
+    fn every_work_has_exactly_its_thumbnail() {
+        let public_dir = default_public_dir();
+        let expected: BTreeSet<PathBuf> = Slug::iter()
+            .map(|slug| public_dir.join(slug.thumbnail_path()))
+            .collect();
+        let found: BTreeSet<PathBuf> = fs::read_dir(public_dir.join(works::THUMBNAIL_DIR))
+            .expect("Thumbnail directory should exist")
+            .map(|entry| entry.unwrap().path())
+            .collect();
+
+        let missing: Vec<_> = expected.difference(&found).collect();
+        let orphaned: Vec<_> = found.difference(&expected).collect();
+        assert!(missing.is_empty(), "Missing thumbnails, run `cargo run -p renderer -- thumbnails`: {missing:?}");
+        assert!(orphaned.is_empty(), "Thumbnails without a work: {orphaned:?}");
+    }
+}
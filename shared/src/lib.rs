@@ -1 +1,119 @@
+// ------ Language ------
 
+/// Cookie holding the language a visitor picked explicitly. It outranks `Accept-Language`.
+pub const LANGUAGE_COOKIE: &str = "language";
+
+/// Japanese is the site's primary language and lives at the unprefixed routes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Language {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Ja => "ja",
+            Language::En => "en",
+        }
+    }
+
+    /// Accepts primary language subtags as well, e.g. `en-GB`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next()?;
+        match primary.to_ascii_lowercase().as_str() {
+            "ja" => Some(Language::Ja),
+            "en" => Some(Language::En),
+            _ => None,
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            Language::Ja => Language::En,
+            Language::En => Language::Ja,
+        }
+    }
+
+    /// Prefixes an absolute site path with the language segment where needed.
+    pub fn localize(&self, path: &str) -> String {
+        match self {
+            Language::Ja => path.to_string(),
+            Language::En => format!("/en{}", path.trim_end_matches('/')),
+        }
+    }
+}
+
+/// The visitor's stored choice if valid, otherwise the best supported language in `Accept-Language`.
+pub fn negotiate_language(cookie: Option<&str>, accept_language: Option<&str>) -> Language {
+    cookie.and_then(Language::from_code)
+        .or_else(|| accept_language.and_then(preferred_language))
+        .unwrap_or_default()
+}
+
+/// Highest-weighted supported language of an `Accept-Language` value like `en-US,en;q=0.9,ja;q=0.8`.
+/// Ties keep the header's order.
+pub fn preferred_language(accept_language: &str) -> Option<Language> {
+    let mut candidates = accept_language.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let language = Language::from_code(parts.next()?)?;
+            let weight = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |weight| weight.trim().parse::<f32>().ok())?;
+            (weight > 0.0).then_some((language, weight))
+        })
+        .collect::<Vec<_>>();
+    // Stable, so the first of equally weighted languages wins
+    candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    candidates.first().map(|(language, _)| *language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highest_weight_wins() {
+        assert_eq!(preferred_language("ja;q=0.5,en;q=0.8"), Some(Language::En));
+        assert_eq!(preferred_language("en;q=0.3, ja"), Some(Language::Ja));
+        assert_eq!(preferred_language("fr,en-US;q=0.9,ja;q=0.8"), Some(Language::En));
+    }
+
+    #[test]
+    fn equal_weights_keep_header_order() {
+        assert_eq!(preferred_language("en,ja"), Some(Language::En));
+        assert_eq!(preferred_language("ja;q=0.7,en;q=0.7"), Some(Language::Ja));
+    }
+
+    #[test]
+    fn region_subtags_match_their_language() {
+        assert_eq!(preferred_language("ja-JP"), Some(Language::Ja));
+        assert_eq!(preferred_language("EN_gb"), Some(Language::En));
+    }
+
+    #[test]
+    fn zero_weight_excludes_a_language() {
+        assert_eq!(preferred_language("en;q=0,ja;q=0.1"), Some(Language::Ja));
+        assert_eq!(preferred_language("en;q=0"), None);
+    }
+
+    #[test]
+    fn malformed_or_empty_headers_fall_back() {
+        assert_eq!(preferred_language(""), None);
+        assert_eq!(preferred_language(",;,"), None);
+        assert_eq!(preferred_language("en;q=high,ja;q=0.2"), Some(Language::Ja));
+        assert_eq!(preferred_language("fr-FR,de;q=0.9"), None);
+        assert_eq!(negotiate_language(None, Some("garbage;;q=")), Language::Ja);
+        assert_eq!(negotiate_language(None, None), Language::default());
+    }
+
+    #[test]
+    fn cookie_overrides_the_header() {
+        assert_eq!(negotiate_language(Some("ja"), Some("en-US,en")), Language::Ja);
+        assert_eq!(negotiate_language(Some("en"), Some("ja-JP")), Language::En);
+        // An unknown stored value is ignored
+        assert_eq!(negotiate_language(Some("xx"), Some("en")), Language::En);
+    }
+}
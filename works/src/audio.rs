@@ -0,0 +1,346 @@
+use std::f32::consts::PI;
+use wgpu::{*, util::*};
+
+/// Samples per analysis window. Half of it becomes spectrum bins.
+pub const FFT_SIZE: usize = 1024;
+/// Width of the audio texture: spectrum bins in row 0, waveform samples in row 1.
+pub const AUDIO_BINS: usize = FFT_SIZE / 2;
+
+/// WGSL declarations of the audio bindings.
+pub const AUDIO_WGSL: &str = include_str!("./audio.wgsl");
+
+// Same dB window as WebAudio's `AnalyserNode` defaults
+const MIN_DECIBELS: f32 = -100.0;
+const MAX_DECIBELS: f32 = -30.0;
+const SMOOTHING: f32 = 0.8;
+
+const BASS_HZ: (f32, f32) = (20.0, 250.0);
+const MID_HZ: (f32, f32) = (250.0, 4000.0);
+const HIGH_HZ: (f32, f32) = (4000.0, 16000.0);
+
+// ------ Analysis ------
+
+/// One analyzed window, ready to upload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioFrame {
+    /// Normalized magnitude per bin, 0.0 at `MIN_DECIBELS` and 1.0 at `MAX_DECIBELS`.
+    pub spectrum: Vec<f32>,
+    /// Samples mapped from -1.0..1.0 to 0.0..1.0.
+    pub waveform: Vec<f32>,
+    pub levels: AudioLevels,
+}
+
+impl Default for AudioFrame {
+    fn default() -> Self {
+        Self {
+            spectrum: vec![0.0; AUDIO_BINS],
+            waveform: vec![0.5; AUDIO_BINS],
+            levels: AudioLevels::default(),
+        }
+    }
+}
+
+/// Mean normalized spectrum per band, plus the RMS of the waveform.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct AudioLevels {
+    pub bass: f32,
+    pub mid: f32,
+    pub high: f32,
+    pub volume: f32,
+}
+
+/// Turns time-domain windows into spectrum, waveform and band levels,
+/// smoothing the spectrum over time like WebAudio's `AnalyserNode`.
+pub struct AudioAnalyzer {
+    sample_rate: f32,
+    window: Vec<f32>,
+    smoothed: Vec<f32>,
+}
+
+impl AudioAnalyzer {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            window: hann_window(FFT_SIZE),
+            smoothed: vec![0.0; AUDIO_BINS],
+        }
+    }
+
+    /// `samples` is zero-padded or truncated to `FFT_SIZE`.
+    pub fn analyze(&mut self, samples: &[f32]) -> AudioFrame {
+        let mut buffer: Vec<(f32, f32)> = (0..FFT_SIZE)
+            .map(|i| (samples.get(i).copied().unwrap_or(0.0) * self.window[i], 0.0))
+            .collect();
+        fft(&mut buffer);
+
+        for (smoothed, (re, im)) in self.smoothed.iter_mut().zip(&buffer) {
+            let magnitude = (re * re + im * im).sqrt() / FFT_SIZE as f32;
+            *smoothed = SMOOTHING * *smoothed + (1.0 - SMOOTHING) * magnitude;
+        }
+        let spectrum: Vec<f32> = self.smoothed.iter().copied().map(normalize_magnitude).collect();
+
+        let waveform = (0..AUDIO_BINS)
+            .map(|i| samples.get(i).copied().unwrap_or(0.0).clamp(-1.0, 1.0) * 0.5 + 0.5)
+            .collect();
+        let volume = match samples.is_empty() {
+            true => 0.0,
+            false => (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt(),
+        };
+        let levels = AudioLevels {
+            bass: band_level(&spectrum, BASS_HZ, self.sample_rate),
+            mid: band_level(&spectrum, MID_HZ, self.sample_rate),
+            high: band_level(&spectrum, HIGH_HZ, self.sample_rate),
+            volume,
+        };
+
+        AudioFrame { spectrum, waveform, levels }
+    }
+}
+
+fn hann_window(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / len as f32).cos())
+        .collect()
+}
+
+fn normalize_magnitude(magnitude: f32) -> f32 {
+    let decibels = 20.0 * magnitude.max(f32::MIN_POSITIVE).log10();
+    ((decibels - MIN_DECIBELS) / (MAX_DECIBELS - MIN_DECIBELS)).clamp(0.0, 1.0)
+}
+
+/// Bins whose center frequency falls in `low..high`.
+fn band_bins((low, high): (f32, f32), sample_rate: f32) -> std::ops::Range<usize> {
+    let bin_width = sample_rate / FFT_SIZE as f32;
+    let start = ((low / bin_width).ceil() as usize).min(AUDIO_BINS);
+    let end = ((high / bin_width).ceil() as usize).clamp(start, AUDIO_BINS);
+    start..end
+}
+
+fn band_level(spectrum: &[f32], band: (f32, f32), sample_rate: f32) -> f32 {
+    let bins = band_bins(band, sample_rate);
+    match bins.is_empty() {
+        true => 0.0,
+        false => spectrum[bins.clone()].iter().sum::<f32>() / bins.len() as f32,
+    }
+}
+
+/// In-place iterative radix-2 FFT. `buffer.len()` must be a power of two.
+fn fft(buffer: &mut [(f32, f32)]) {
+    let n = buffer.len();
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (re, im) = buffer[start + k + len / 2];
+                let t = (re * cos - im * sin, re * sin + im * cos);
+                let u = buffer[start + k];
+                buffer[start + k] = (u.0 + t.0, u.1 + t.1);
+                buffer[start + k + len / 2] = (u.0 - t.0, u.1 - t.1);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// ------ Binding ------
+
+/// Audio texture and level uniform. Works opt in through `Inputs::audio`, add
+/// `bind_group_layout` to their pipeline layout and declare `AUDIO_WGSL` at the matching group.
+pub struct AudioBinding {
+    pub bind_group_layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+    texture: Texture,
+    levels_buffer: Buffer,
+}
+
+impl AudioBinding {
+    pub fn new(device: &Device) -> Self {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("audio"),
+            size: Extent3d {
+                width: AUDIO_BINS as u32,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("audio"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+        let levels_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("audio levels"),
+            contents: bytemuck::cast_slice(&[AudioLevels::default()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("audio"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("audio"),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&texture.create_view(&TextureViewDescriptor::default())),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: levels_buffer.as_entire_binding(),
+                },
+            ],
+            layout: &bind_group_layout,
+        });
+
+        Self {
+            bind_group_layout,
+            bind_group,
+            texture,
+            levels_buffer,
+        }
+    }
+
+    pub fn update(&self, queue: &Queue, frame: &AudioFrame) {
+        let texels: Vec<u8> = frame.spectrum.iter()
+            .chain(&frame.waveform)
+            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect();
+        queue.write_texture(
+            self.texture.as_image_copy(),
+            &texels,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(AUDIO_BINS as u32),
+                rows_per_image: None,
+            },
+            self.texture.size(),
+        );
+        queue.write_buffer(&self.levels_buffer, 0, bytemuck::cast_slice(&[frame.levels]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fft_of_a_sine_peaks_at_its_bin() {
+        let n = 64;
+        let cycles = 5;
+        let mut buffer: Vec<(f32, f32)> = (0..n)
+            .map(|i| ((2.0 * PI * cycles as f32 * i as f32 / n as f32).sin(), 0.0))
+            .collect();
+        fft(&mut buffer);
+
+        let magnitudes: Vec<f32> = buffer[..n / 2].iter().map(|(re, im)| (re * re + im * im).sqrt()).collect();
+        for (bin, magnitude) in magnitudes.iter().enumerate() {
+            match bin == cycles {
+                // A unit sine puts half its energy in each of the two mirrored bins
+                true => assert!((magnitude - n as f32 / 2.0).abs() < 1e-3, "bin {bin}: {magnitude}"),
+                false => assert!(*magnitude < 1e-3, "bin {bin}: {magnitude}"),
+            }
+        }
+    }
+
+    #[test]
+    fn silence_has_zero_levels() {
+        let mut analyzer = AudioAnalyzer::new(48000.0);
+        for samples in [vec![], vec![0.0; FFT_SIZE]] {
+            let frame = analyzer.analyze(&samples);
+            assert_eq!(frame.levels, AudioLevels::default());
+            assert!(frame.spectrum.iter().all(|value| *value == 0.0));
+            assert!(frame.waveform.iter().all(|value| *value == 0.5));
+        }
+    }
+
+    #[test]
+    fn band_bins_cover_the_bins_centered_in_the_band() {
+        let sample_rate = 48000.0;
+        let bin_width = sample_rate / FFT_SIZE as f32;
+        for band in [BASS_HZ, MID_HZ, HIGH_HZ] {
+            let bins = band_bins(band, sample_rate);
+            assert!(!bins.is_empty());
+            for bin in bins.clone() {
+                let center = bin as f32 * bin_width;
+                assert!(band.0 <= center && center < band.1, "bin {bin} at {center} Hz outside {band:?}");
+            }
+            assert!((bins.start - 1) as f32 * bin_width < band.0);
+            assert!(bins.end as f32 * bin_width >= band.1);
+        }
+        assert_eq!(band_bins(BASS_HZ, sample_rate), 1..6);
+    }
+
+    #[test]
+    fn band_bins_stop_at_the_nyquist_frequency() {
+        // 16 kHz is above the 8 kHz Nyquist frequency at a 16 kHz sample rate
+        assert_eq!(band_bins(HIGH_HZ, 16000.0), 256..AUDIO_BINS);
+        // and the whole band is above it at 4 kHz
+        assert!(band_bins(HIGH_HZ, 4000.0).is_empty());
+    }
+
+    #[test]
+    fn normalize_magnitude_clamps_to_the_decibel_window() {
+        assert_eq!(normalize_magnitude(0.0), 0.0);
+        assert_eq!(normalize_magnitude(10f32.powf(MIN_DECIBELS / 20.0) / 10.0), 0.0);
+        assert_eq!(normalize_magnitude(1.0), 1.0);
+        let middle = 10f32.powf((MIN_DECIBELS + MAX_DECIBELS) / 2.0 / 20.0);
+        assert!((normalize_magnitude(middle) - 0.5).abs() < 1e-4);
+    }
+}
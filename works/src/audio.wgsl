@@ -0,0 +1,11 @@
+// Audio input, bound by works that opt in with `Inputs::audio`:
+//   @group(N) @binding(0) var audio_texture: texture_2d<f32>;  // row 0: spectrum, row 1: waveform (512 texels each)
+//   @group(N) @binding(1) var audio_sampler: sampler;
+//   @group(N) @binding(2) var<uniform> audio: AudioLevels;
+struct AudioLevels {
+    bass: f32,
+    mid: f32,
+    high: f32,
+    volume: f32,
+}
+
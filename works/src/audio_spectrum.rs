@@ -0,0 +1,38 @@
+use wgpu::*;
+use super::{
+    base::fragment::{FragmentWork, InputBinding},
+    AudioFrame,
+    FrameInput,
+    GpuContext,
+    Inputs,
+    Shader,
+    AUDIO_WGSL,
+    PALETTE_WGSL,
+};
+
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 2.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[PALETTE_WGSL, AUDIO_WGSL, include_str!("./audio_spectrum.wgsl")];
+
+pub struct ShaderWork(FragmentWork);
+
+impl Shader for ShaderWork {
+    fn new(ctx: &GpuContext) -> Self {
+        // The audio texture starts zeroed, which would read as a waveform at its lowest until audio plays
+        ctx.audio.update(&ctx.queue, &AudioFrame::default());
+        Self(FragmentWork::new(ctx, "audio_spectrum.wgsl", SOURCE, InputBinding::Audio))
+    }
+
+    fn inputs() -> Inputs {
+        Inputs {
+            audio: true,
+            ..Inputs::default()
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput) {
+        self.0.render(ctx, target, input)
+    }
+}
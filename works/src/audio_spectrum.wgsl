@@ -0,0 +1,66 @@
+@group(0) @binding(0)
+var<uniform> palette: Palette;
+
+struct Uniform {
+    resolution: vec2<f32>,
+    time: f32,
+}
+
+@group(1) @binding(0)
+var<uniform> uniforms: Uniform;
+
+@group(2) @binding(0)
+var audio_texture: texture_2d<f32>;
+@group(2) @binding(1)
+var audio_sampler: sampler;
+@group(2) @binding(2)
+var<uniform> audio: AudioLevels;
+
+const BARS: f32 = 48.0;
+
+@vertex
+fn vs_main(@builtin(vertex_index) i_vertex: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((i_vertex << 1u) & 2u), f32(i_vertex & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+// Low frequencies carry most of the energy, so they get more of the bars
+fn spectrum(x: f32) -> f32 {
+    return textureSampleLevel(audio_texture, audio_sampler, vec2<f32>(x * x, 0.25), 0.0).r;
+}
+
+fn waveform(x: f32) -> f32 {
+    return textureSampleLevel(audio_texture, audio_sampler, vec2<f32>(x, 0.75), 0.0).r;
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = pos.xy / uniforms.resolution;
+    let height = 1.0 - uv.y;
+    let pixel = 1.0 / uniforms.resolution.y;
+
+    // Background breathes with the bass
+    let center = (uv - 0.5) * vec2<f32>(uniforms.resolution.x / uniforms.resolution.y, 1.0);
+    let glow = audio.bass * exp(-4.0 * dot(center, center));
+    var color = mix(palette.base, palette.mantle, uv.y) + palette.mauve * glow * 0.5;
+
+    // Spectrum bars along the bottom, at least a sliver tall while silent
+    let bar = floor(uv.x * BARS);
+    let level = spectrum((bar + 0.5) / BARS);
+    let bar_top = 0.02 + level * 0.7;
+    if fract(uv.x * BARS) < 0.75 && height < bar_top {
+        color = mix(palette.blue, palette.mauve, height / 0.72);
+    }
+
+    // Waveform across the middle, thicker when loud
+    let wave = 0.5 + (waveform(uv.x) - 0.5) * 0.5;
+    let thickness = (1.5 + 6.0 * audio.volume) * pixel;
+    let line = 1.0 - smoothstep(thickness, thickness + pixel, abs(height - wave));
+    color = mix(color, palette.peach, line);
+
+    // Highs sparkle along the top edge
+    let shimmer = audio.high * smoothstep(0.85, 1.0, height) * (0.5 + 0.5 * sin(uv.x * 40.0 + uniforms.time * 6.0));
+    color = mix(color, palette.sky, shimmer);
+
+    return vec4<f32>(color.rgb, 1.0);
+}
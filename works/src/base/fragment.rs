@@ -0,0 +1,189 @@
+use std::borrow::Cow;
+use wgpu::{*, util::*};
+use crate::{FrameInput, GpuContext};
+
+/// Context binding a fragment work reads at group 2.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum InputBinding {
+    Audio,
+    Keyboard,
+    Pointer,
+}
+
+impl InputBinding {
+    fn bind_group_layout<'c>(&self, ctx: &'c GpuContext) -> &'c BindGroupLayout {
+        match self {
+            InputBinding::Audio => &ctx.audio.bind_group_layout,
+            InputBinding::Keyboard => &ctx.keyboard.bind_group_layout,
+            InputBinding::Pointer => &ctx.pointer.bind_group_layout,
+        }
+    }
+
+    fn bind_group<'c>(&self, ctx: &'c GpuContext) -> &'c BindGroup {
+        match self {
+            InputBinding::Audio => &ctx.audio.bind_group,
+            InputBinding::Keyboard => &ctx.keyboard.bind_group,
+            InputBinding::Pointer => &ctx.pointer.bind_group,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniform {
+    resolution: [f32; 2],
+    time: f32,
+    _padding: f32,
+}
+
+impl Uniform {
+    fn new() -> Self {
+        Self {
+            resolution: [0.0, 0.0],
+            time: 0.0,
+            _padding: 0.0,
+        }
+    }
+
+    fn update(&mut self, ctx: &GpuContext, input: &FrameInput) {
+        self.resolution = [ctx.config.width as f32, ctx.config.height as f32];
+        self.time = input.time;
+    }
+}
+
+/// A work drawn entirely by its `fs_main`, over a single triangle from `vs_main` covering the target.
+/// Binds the palette at group 0, `Uniform` (resolution and time) at group 1 and `input` at group 2.
+pub(crate) struct FragmentWork {
+    render_pipeline: RenderPipeline,
+    uniform: Uniform,
+    uniform_buffer: Buffer,
+    uniform_bind_group: BindGroup,
+    input: InputBinding,
+}
+
+impl FragmentWork {
+    pub(crate) fn new(ctx: &GpuContext, label: &str, source: &[&str], input: InputBinding) -> Self {
+        let shader = ctx.device.create_shader_module(ShaderModuleDescriptor {
+            label: Some(label),
+            source: ShaderSource::Wgsl(Cow::Owned(source.concat())),
+        });
+
+        // Buffers
+        let uniform = Uniform::new();
+        let uniform_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        // Bind groups
+        let uniform_bind_group_layout = ctx.device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let uniform_bind_group = ctx.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }
+            ],
+            layout: &uniform_bind_group_layout,
+        });
+
+        // Render pipeline
+        let pipeline_layout = ctx.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[
+                &ctx.palette.bind_group_layout,
+                &uniform_bind_group_layout,
+                input.bind_group_layout(ctx),
+            ],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline = ctx.device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format: ctx.target_format(),
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+        });
+
+        Self {
+            render_pipeline,
+            uniform,
+            uniform_buffer,
+            uniform_bind_group,
+            input,
+        }
+    }
+
+    pub(crate) fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput) {
+        self.uniform.update(ctx, input);
+        ctx.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+
+        let view = target.create_view(&TextureViewDescriptor {
+            format: Some(ctx.target_format()),
+            ..Default::default()
+        });
+        let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: None,
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(ctx.clear_color()),
+                        store: StoreOp::Store,
+                    }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: ctx.timestamp_writes(),
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &ctx.palette.bind_group, &[]);
+            render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+            render_pass.set_bind_group(2, self.input.bind_group(ctx), &[]);
+            // A single triangle covering the whole target
+            render_pass.draw(0..3, 0..1);
+        }
+
+        ctx.resolve_timestamps(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
@@ -1,10 +1,9 @@
+use std::borrow::Cow;
 use wgpu::{*, util::*};
-use zoon::{AnimationLoop, chrono::Duration};
 use super::{
+    FrameInput,
     GpuContext,
     Shader,
-    ANIMATION_LOOP,
-    CANVAS_POINTER_POSITION,
 };
 
 #[repr(C)]
@@ -64,23 +63,37 @@ impl Uniform {
         }
     }
 
-    fn update(&mut self, ctx: &GpuContext, delta: Duration) {
+    fn update(&mut self, ctx: &GpuContext, input: &FrameInput) {
         self.resolution = [ctx.config.width as f32, ctx.config.height as f32];
-        self.time += delta.num_milliseconds() as f32 / 1000.0;
-        self.frame += 1;
-        let (x, y) = CANVAS_POINTER_POSITION.get();
-        self.mouse = [x as f32, y as f32];
+        self.time = input.time;
+        self.frame = input.frame;
+        let (x, y) = input.pointer;
+        self.mouse = [x, y];
     }
 }
 
 
-#[derive(Debug, Default, PartialEq)]
-pub struct ShaderWork;
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 12.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[include_str!("./glslsandbox_example.wgsl")];
+
+pub struct ShaderWork {
+    render_pipeline: RenderPipeline,
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    uniform: Uniform,
+    uniform_buffer: Buffer,
+    uniform_bind_group: BindGroup,
+}
 
 impl Shader for ShaderWork {
-    async fn run(canvas: zoon::web_sys::HtmlCanvasElement) {
-        let mut ctx = GpuContext::new(canvas).await;
-        let shader = ctx.device.create_shader_module(include_wgsl!("./glslsandbox_example.wgsl"));
+    fn new(ctx: &GpuContext) -> Self {
+        let shader = ctx.device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("glslsandbox_example.wgsl"),
+            source: ShaderSource::Wgsl(Cow::Owned(SOURCE.concat())),
+        });
 
         // Buffers
         let vertex_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
@@ -93,7 +106,7 @@ impl Shader for ShaderWork {
             contents: bytemuck::cast_slice(INDICES),
             usage: BufferUsages::INDEX,
         });
-        let mut uniform = Uniform::new();
+        let uniform = Uniform::new();
         let uniform_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
             label: None,
             contents: bytemuck::cast_slice(&[uniform]),
@@ -149,7 +162,7 @@ impl Shader for ShaderWork {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(ColorTargetState {
-                    format: ctx.config.format,
+                    format: ctx.target_format(),
                     blend: Some(BlendState::REPLACE),
                     write_mask: ColorWrites::ALL,
                 })],
@@ -161,57 +174,52 @@ impl Shader for ShaderWork {
             multiview: None,
         });
 
-        let animation_loop = AnimationLoop::new(move |delta| {
-            ctx.resize();
-            uniform.update(&ctx, delta);
-            ctx.queue.write_buffer(&uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
-
-            let Ok(frame) = ctx.surface.get_current_texture() else {
-                eprintln!("Target texture not found.");
-                return;
-            };
-            let view = frame.texture.create_view(&TextureViewDescriptor {
-                format: Some(ctx.config.format.add_srgb_suffix()),
-                ..Default::default()
-            });
-            let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            uniform,
+            uniform_buffer,
+            uniform_bind_group,
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput) {
+        self.uniform.update(ctx, input);
+        ctx.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+
+        let view = target.create_view(&TextureViewDescriptor {
+            format: Some(ctx.target_format()),
+            ..Default::default()
+        });
+        let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: None,
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(ctx.clear_color()),
+                        store: StoreOp::Store,
+                    }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: ctx.timestamp_writes(),
+                occlusion_query_set: None,
             });
 
-            {
-                let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
-                    label: None,
-                    color_attachments: &[Some(RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
-                        ops: Operations {
-                            load: LoadOp::Clear(
-                                Color {
-                                    r: 0.1,
-                                    g: 0.2,
-                                    b: 0.3,
-                                    a: 1.0,
-                                }
-                            ),
-                            store: StoreOp::Store,
-                        }
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
-
-                render_pass.set_pipeline(&render_pipeline);
-                render_pass.set_bind_group(0, &uniform_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                render_pass.set_index_buffer(index_buffer.slice(..), IndexFormat::Uint16);
-                render_pass.draw_indexed(0..(INDICES.len() as u32), 0, 0..1);
-            }
-
-            ctx.queue.submit(std::iter::once(encoder.finish()));
-            frame.present();
-        });
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint16);
+            render_pass.draw_indexed(0..(INDICES.len() as u32), 0, 0..1);
+        }
 
-        ANIMATION_LOOP.set(Some(animation_loop));
+        ctx.resolve_timestamps(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
     }
 }
@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+use wgpu::*;
+use super::{
+    FrameInput,
+    GpuContext,
+    Shader,
+    PALETTE_WGSL,
+};
+
+
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 0.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[PALETTE_WGSL, include_str!("./hello_triangle.wgsl")];
+
+pub struct ShaderWork {
+    render_pipeline: RenderPipeline,
+}
+
+impl Shader for ShaderWork {
+    fn new(ctx: &GpuContext) -> Self {
+        let shader = ctx.device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("hello_triangle.wgsl"),
+            source: ShaderSource::Wgsl(Cow::Owned(SOURCE.concat())),
+        });
+
+        // Render pipeline
+        let pipeline_layout = ctx.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[
+                &ctx.palette.bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline = ctx.device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format: ctx.target_format(),
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),   // topology: TriangleList, front_face: Ccw などをココで設定できる
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+        });
+
+        Self {
+            render_pipeline,
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, _: &FrameInput) {
+        let view = target.create_view(&TextureViewDescriptor {
+            format: Some(ctx.target_format()),
+            ..Default::default()
+        });
+        let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: None,
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(ctx.clear_color()),
+                        store: StoreOp::Store,
+                    }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: ctx.timestamp_writes(),
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &ctx.palette.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        ctx.resolve_timestamps(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
@@ -1,3 +1,6 @@
+@group(0) @binding(0)
+var<uniform> palette: Palette;
+
 struct VertexOutput {
     @builtin(position) pos: vec4<f32>,
 }
@@ -14,5 +17,5 @@ fn vs_main(@builtin(vertex_index) i_vertex: u32) -> VertexOutput {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4f(0.3, 0.2, 0.1, 1.0);
+    return palette.peach;
 }
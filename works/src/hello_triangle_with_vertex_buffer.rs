@@ -1,9 +1,9 @@
+use std::borrow::Cow;
 use wgpu::{*, util::*};
-use zoon::AnimationLoop;
 use super::{
+    FrameInput,
     GpuContext,
     Shader,
-    ANIMATION_LOOP,
 };
 
 #[repr(C)]
@@ -43,13 +43,23 @@ const VERTICES: &[Vertex] = &[
 ];
 
 
-#[derive(Debug, Default, PartialEq)]
-pub struct ShaderWork;
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 0.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[include_str!("./hello_triangle_with_vertex_buffer.wgsl")];
+
+pub struct ShaderWork {
+    render_pipeline: RenderPipeline,
+    vertex_buffer: Buffer,
+}
 
 impl Shader for ShaderWork {
-    async fn run(canvas: zoon::web_sys::HtmlCanvasElement) {
-        let mut ctx = GpuContext::new(canvas).await;
-        let shader = ctx.device.create_shader_module(include_wgsl!("./hello_triangle_with_vertex_buffer.wgsl"));
+    fn new(ctx: &GpuContext) -> Self {
+        let shader = ctx.device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("hello_triangle_with_vertex_buffer.wgsl"),
+            source: ShaderSource::Wgsl(Cow::Owned(SOURCE.concat())),
+        });
     
         // Buffers
         let vertex_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
@@ -57,7 +67,7 @@ impl Shader for ShaderWork {
             contents: bytemuck::cast_slice(VERTICES),
             usage: BufferUsages::VERTEX,
         });
-    
+
         // Render pipeline
         let pipeline_layout = ctx.device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: None,
@@ -79,7 +89,7 @@ impl Shader for ShaderWork {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(ColorTargetState {
-                    format: ctx.config.format,
+                    format: ctx.target_format(),
                     blend: Some(BlendState::REPLACE),
                     write_mask: ColorWrites::ALL,
                 })],
@@ -90,52 +100,44 @@ impl Shader for ShaderWork {
             multisample: MultisampleState::default(),
             multiview: None,
         });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, _: &FrameInput) {
+        let view = target.create_view(&TextureViewDescriptor {
+            format: Some(ctx.target_format()),
+            ..Default::default()
+        });
+        let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: None,
+        });
     
-        // Animation loop
-        let animation_loop = AnimationLoop::new(move |_| {
-            ctx.resize();
-    
-            let Ok(frame) = ctx.surface.get_current_texture() else {
-                eprintln!("Target texture not found.");
-                return;
-            };
-            let view = frame.texture.create_view(&TextureViewDescriptor::default());
-            let mut encoder = ctx.device.create_command_encoder(&CommandEncoderDescriptor {
+        {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(ctx.clear_color()),
+                        store: StoreOp::Store,
+                    }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: ctx.timestamp_writes(),
+                occlusion_query_set: None,
             });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..(VERTICES.len() as u32), 0..1);
+        }
     
-            {
-                let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
-                    label: None,
-                    color_attachments: &[Some(RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
-                        ops: Operations {
-                            load: LoadOp::Clear(
-                                Color {
-                                    r: 0.1,
-                                    g: 0.2,
-                                    b: 0.3,
-                                    a: 1.0,
-                                }
-                            ),
-                            store: StoreOp::Store,
-                        }
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
-    
-                render_pass.set_pipeline(&render_pipeline);
-                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                render_pass.draw(0..(VERTICES.len() as u32), 0..1);
-            }
-    
-            ctx.queue.submit(std::iter::once(encoder.finish()));
-            frame.present();
-        });
-    
-        ANIMATION_LOOP.set(Some(animation_loop));
+        ctx.resolve_timestamps(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,222 @@
+use wgpu::*;
+
+/// Key codes are the legacy `KeyboardEvent.keyCode` values, as on Shadertoy.
+pub const KEY_CODES: usize = 256;
+
+/// WGSL notes for the keyboard texture.
+pub const KEYBOARD_WGSL: &str = include_str!("./keyboard.wgsl");
+
+const DOWN: usize = 0;
+const PRESSED: usize = 1;
+const TOGGLED: usize = 2;
+
+/// Per-key state. `pressed` holds for the single frame the key went down.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardState {
+    rows: [[bool; KEY_CODES]; 3],
+    changed: bool,
+}
+
+impl Default for KeyboardState {
+    fn default() -> Self {
+        Self {
+            rows: [[false; KEY_CODES]; 3],
+            changed: false,
+        }
+    }
+}
+
+impl KeyboardState {
+    pub fn key_down(&mut self, code: u32) {
+        let Some(code) = key_index(code) else { return };
+        // Ignore auto-repeat
+        if !self.rows[DOWN][code] {
+            self.rows[DOWN][code] = true;
+            self.rows[PRESSED][code] = true;
+            self.rows[TOGGLED][code] = !self.rows[TOGGLED][code];
+            self.changed = true;
+        }
+    }
+
+    pub fn key_up(&mut self, code: u32) {
+        let Some(code) = key_index(code) else { return };
+        if self.rows[DOWN][code] {
+            self.rows[DOWN][code] = false;
+            self.changed = true;
+        }
+    }
+
+    /// Releases every key, e.g. when the canvas loses focus and key-ups would go elsewhere.
+    pub fn release_all(&mut self) {
+        if self.rows[DOWN].contains(&true) {
+            self.rows[DOWN] = [false; KEY_CODES];
+            self.changed = true;
+        }
+    }
+
+    /// Returns texels to upload if anything changed since the last call, and ends the frame.
+    pub fn take_frame(&mut self) -> Option<Vec<u8>> {
+        if !self.changed {
+            return None;
+        }
+        let texels = self.rows.iter()
+            .flatten()
+            .map(|&on| if on { 255 } else { 0 })
+            .collect();
+        // `pressed` lasts one frame, so clearing it is another change to upload next frame
+        self.changed = self.rows[PRESSED].contains(&true);
+        self.rows[PRESSED] = [false; KEY_CODES];
+
+        Some(texels)
+    }
+}
+
+fn key_index(code: u32) -> Option<usize> {
+    (code < KEY_CODES as u32).then_some(code as usize)
+}
+
+/// 256×3 texture of key states: row 0 down, row 1 pressed this frame, row 2 toggled.
+/// Works opt in through `Inputs::keyboard` and add `bind_group_layout` to their pipeline layout.
+pub struct KeyboardBinding {
+    pub bind_group_layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+    texture: Texture,
+}
+
+impl KeyboardBinding {
+    pub fn new(device: &Device) -> Self {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("keyboard"),
+            size: Extent3d {
+                width: KEY_CODES as u32,
+                height: 3,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("keyboard"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("keyboard"),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&texture.create_view(&TextureViewDescriptor::default())),
+                },
+            ],
+            layout: &bind_group_layout,
+        });
+
+        Self {
+            bind_group_layout,
+            bind_group,
+            texture,
+        }
+    }
+
+    pub fn update(&self, queue: &Queue, state: &mut KeyboardState) {
+        let Some(texels) = state.take_frame() else { return };
+        queue.write_texture(
+            self.texture.as_image_copy(),
+            &texels,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(KEY_CODES as u32),
+                rows_per_image: None,
+            },
+            self.texture.size(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACE: u32 = 32;
+
+    fn texel(texels: &[u8], row: usize, code: u32) -> bool {
+        texels[row * KEY_CODES + code as usize] == 255
+    }
+
+    #[test]
+    fn nothing_to_upload_without_changes() {
+        let mut state = KeyboardState::default();
+        assert_eq!(state.take_frame(), None);
+        state.key_up(SPACE);
+        state.release_all();
+        assert_eq!(state.take_frame(), None);
+    }
+
+    #[test]
+    fn pressed_lasts_one_frame() {
+        let mut state = KeyboardState::default();
+        state.key_down(SPACE);
+        let texels = state.take_frame().unwrap();
+        assert!(texel(&texels, DOWN, SPACE) && texel(&texels, PRESSED, SPACE));
+        // Clearing `pressed` is uploaded on the next frame, then nothing changes while the key is held
+        let texels = state.take_frame().unwrap();
+        assert!(texel(&texels, DOWN, SPACE) && !texel(&texels, PRESSED, SPACE));
+        assert_eq!(state.take_frame(), None);
+    }
+
+    #[test]
+    fn ignores_auto_repeat() {
+        let mut state = KeyboardState::default();
+        state.key_down(SPACE);
+        state.take_frame();
+        state.take_frame();
+        state.key_down(SPACE);
+        state.key_down(SPACE);
+        assert_eq!(state.take_frame(), None);
+    }
+
+    #[test]
+    fn toggles_on_every_press() {
+        let mut state = KeyboardState::default();
+        state.key_down(SPACE);
+        assert!(texel(&state.take_frame().unwrap(), TOGGLED, SPACE));
+        state.key_up(SPACE);
+        let texels = state.take_frame().unwrap();
+        assert!(!texel(&texels, DOWN, SPACE) && texel(&texels, TOGGLED, SPACE));
+        state.key_down(SPACE);
+        assert!(!texel(&state.take_frame().unwrap(), TOGGLED, SPACE));
+    }
+
+    #[test]
+    fn release_all_keeps_toggles() {
+        let mut state = KeyboardState::default();
+        state.key_down(SPACE);
+        state.key_down(65);
+        state.take_frame();
+        state.release_all();
+        let texels = state.take_frame().unwrap();
+        assert!(!texel(&texels, DOWN, SPACE) && !texel(&texels, DOWN, 65));
+        assert!(texel(&texels, TOGGLED, SPACE) && texel(&texels, TOGGLED, 65));
+    }
+
+    #[test]
+    fn ignores_codes_outside_the_texture() {
+        let mut state = KeyboardState::default();
+        state.key_down(KEY_CODES as u32);
+        assert_eq!(state.take_frame(), None);
+    }
+}
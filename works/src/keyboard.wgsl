@@ -0,0 +1,7 @@
+// Keyboard input, bound by works that opt in with `Inputs::keyboard`:
+//   @group(N) @binding(0) var keyboard_texture: texture_2d<f32>;
+// x is the legacy keyCode, y is 0: down, 1: pressed this frame, 2: toggled
+fn key_state(key_code: u32, row: u32) -> bool {
+    return textureLoad(keyboard_texture, vec2<u32>(key_code, row), 0).r > 0.5;
+}
+
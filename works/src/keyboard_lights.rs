@@ -0,0 +1,35 @@
+use wgpu::*;
+use super::{
+    base::fragment::{FragmentWork, InputBinding},
+    FrameInput,
+    GpuContext,
+    Inputs,
+    Shader,
+    KEYBOARD_WGSL,
+    PALETTE_WGSL,
+};
+
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 1.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[PALETTE_WGSL, KEYBOARD_WGSL, include_str!("./keyboard_lights.wgsl")];
+
+pub struct ShaderWork(FragmentWork);
+
+impl Shader for ShaderWork {
+    fn new(ctx: &GpuContext) -> Self {
+        Self(FragmentWork::new(ctx, "keyboard_lights.wgsl", SOURCE, InputBinding::Keyboard))
+    }
+
+    fn inputs() -> Inputs {
+        Inputs {
+            keyboard: true,
+            ..Inputs::default()
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput) {
+        self.0.render(ctx, target, input)
+    }
+}
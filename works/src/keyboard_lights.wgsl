@@ -0,0 +1,91 @@
+@group(0) @binding(0)
+var<uniform> palette: Palette;
+
+struct Uniform {
+    resolution: vec2<f32>,
+    time: f32,
+}
+
+@group(1) @binding(0)
+var<uniform> uniforms: Uniform;
+
+@group(2) @binding(0)
+var keyboard_texture: texture_2d<f32>;
+
+const DOWN: u32 = 0u;
+const TOGGLED: u32 = 2u;
+
+@vertex
+fn vs_main(@builtin(vertex_index) i_vertex: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((i_vertex << 1u) & 2u), f32(i_vertex & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+// Letter keys of a QWERTY layout; their key codes are the uppercase ASCII codes
+fn key_code(row: i32, column: i32) -> u32 {
+    var top = array<u32, 10>(81u, 87u, 69u, 82u, 84u, 89u, 85u, 73u, 79u, 80u);
+    var middle = array<u32, 9>(65u, 83u, 68u, 70u, 71u, 72u, 74u, 75u, 76u);
+    var bottom = array<u32, 7>(90u, 88u, 67u, 86u, 66u, 78u, 77u);
+    switch row {
+        case 0: { return top[column]; }
+        case 1: { return middle[column]; }
+        default: { return bottom[column]; }
+    }
+}
+
+fn row_length(row: i32) -> i32 {
+    var lengths = array<i32, 3>(10, 9, 7);
+    return lengths[row];
+}
+
+// Each row is shifted right like on a real keyboard
+fn row_offset(row: i32) -> f32 {
+    var offsets = array<f32, 3>(0.0, 0.25, 0.75);
+    return offsets[row];
+}
+
+fn rounded_box(p: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+    let q = abs(p) - half_size + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    // Fit 10.5 × 3 keys with a margin of one key around them
+    let key_size = min(uniforms.resolution.x / 12.5, uniforms.resolution.y / 5.0);
+    let origin = (uniforms.resolution - vec2<f32>(10.5, 3.0) * key_size) * 0.5;
+    let p = (pos.xy - origin) / key_size;
+    let pixel = 1.0 / key_size;
+
+    var color = palette.base;
+    let row = i32(floor(p.y));
+    if row < 0 || row > 2 {
+        return vec4<f32>(color.rgb, 1.0);
+    }
+    let x = p.x - row_offset(row);
+    let column = i32(floor(x));
+    if column < 0 || column >= row_length(row) {
+        return vec4<f32>(color.rgb, 1.0);
+    }
+
+    let code = key_code(row, column);
+    let down = key_state(code, DOWN);
+    let toggled = key_state(code, TOGGLED);
+    let local = vec2<f32>(fract(x), fract(p.y)) - 0.5;
+    let edge = rounded_box(local, vec2<f32>(0.42), 0.12);
+
+    // Toggled keys keep a slowly pulsing outline, held keys light up
+    var key_color = palette.surface0;
+    if down {
+        key_color = palette.peach;
+    }
+    let fill = 1.0 - smoothstep(0.0, pixel, edge);
+    color = mix(color, key_color, fill);
+    if toggled {
+        let pulse = 0.6 + 0.4 * sin(uniforms.time * 3.0 + f32(code));
+        let outline = 1.0 - smoothstep(0.0, pixel, abs(edge + 0.02) - 0.02);
+        color = mix(color, palette.mauve, outline * pulse);
+    }
+
+    return vec4<f32>(color.rgb, 1.0);
+}
@@ -0,0 +1,485 @@
+mod audio;
+mod base;
+mod keyboard;
+mod palette;
+mod pointer;
+mod timing;
+
+pub use audio::{AudioAnalyzer, AudioBinding, AudioFrame, AudioLevels, AUDIO_BINS, AUDIO_WGSL, FFT_SIZE};
+pub use keyboard::{KeyboardBinding, KeyboardState, KEYBOARD_WGSL, KEY_CODES};
+pub use palette::{Palette, PaletteBinding, PALETTE_WGSL};
+pub use pointer::{PointerBinding, PointerEvent, PointerState, MAX_TOUCHES, POINTER_WGSL};
+pub use timing::GpuTimer;
+
+use std::{fmt, rc::Rc, sync::{Arc, atomic::{AtomicBool, Ordering}}};
+use heck::{ToSnakeCase, ToTitleCase};
+use strum::{Display, EnumString, EnumIter};
+use wgpu::*;
+
+
+// ------ Slug ------
+
+/// Directory of gallery thumbnails relative to `public/`.
+pub const THUMBNAIL_DIR: &str = "shaders/thumbnail";
+
+macro_rules! shader_mod {
+    (
+        enum Slug {
+            $($slug:ident),* $(,)?
+        }
+    ) => {
+        paste::paste! {
+            $(mod [< $slug:snake >];)*
+
+            #[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, EnumString, EnumIter)]
+            #[strum(serialize_all = "kebab-case")]
+            pub enum Slug {
+                $($slug,)*
+            }
+
+            impl Slug {
+                /// Builds the work's pipelines and buffers on the given context.
+                pub fn create(&self, ctx: &GpuContext) -> Box<dyn Shader> {
+                    match self {
+                        $(
+                            Slug::$slug => Box::new([< $slug:snake >]::ShaderWork::new(ctx)),
+                        )*
+                    }
+                }
+
+                pub fn inputs(&self) -> Inputs {
+                    match self {
+                        $(
+                            Slug::$slug => <[< $slug:snake >]::ShaderWork as Shader>::inputs(),
+                        )*
+                    }
+                }
+
+                pub fn requirements(&self) -> Requirements {
+                    match self {
+                        $(
+                            Slug::$slug => <[< $slug:snake >]::ShaderWork as Shader>::requirements(),
+                        )*
+                    }
+                }
+
+                /// Exactly the WGSL the work compiles: its `SOURCE` parts joined in order. Shared declarations
+                /// like `PALETTE_WGSL` come before the work's own WGSL, which uses them.
+                pub fn source(&self) -> String {
+                    match self {
+                        $(
+                            Slug::$slug => [< $slug:snake >]::SOURCE.concat(),
+                        )*
+                    }
+                }
+
+                pub fn thumbnail_time(&self) -> f32 {
+                    match self {
+                        $(
+                            Slug::$slug => [< $slug:snake >]::THUMBNAIL_TIME,
+                        )*
+                    }
+                }
+            }
+        }
+    }
+}
+
+shader_mod! {
+    enum Slug {
+        HelloTriangle,
+        HelloTriangleWithVertexBuffer,
+        GlslsandboxExample,
+        AudioSpectrum,
+        KeyboardLights,
+        PointerRipples,
+        // *** Add new shaders here ***
+    }
+}
+
+impl Slug {
+    pub fn title(&self) -> String {
+        self.to_string().to_title_case()
+    }
+
+    pub fn page_url(&self) -> String {
+        format!("/shaders/{}", self)
+    }
+
+    /// Path relative to `public/`.
+    pub fn thumbnail_path(&self) -> String {
+        format!("{}/{}.webp", THUMBNAIL_DIR, self.to_string().to_snake_case())
+    }
+}
+
+// ------ Shared by shader works ------
+
+/// Per-frame input handed to a work by whoever drives it (the browser loop or the headless renderer).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameInput {
+    /// Seconds since the work started.
+    pub time: f32,
+    /// Seconds since the previous frame.
+    pub delta: f32,
+    pub frame: i32,
+    /// Pointer position in CSS pixels from the top-left of the canvas.
+    pub pointer: (f32, f32),
+}
+
+impl FrameInput {
+    pub fn advance(&mut self, delta: f32, pointer: (f32, f32)) {
+        self.time += delta;
+        self.delta = delta;
+        self.frame += 1;
+        self.pointer = pointer;
+    }
+}
+
+/// Optional inputs a work listens to. The page only wires up what a work asks for.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Inputs {
+    /// Binds `GpuContext::audio`.
+    pub audio: bool,
+    /// Track relative to `public/` offered to play, besides the microphone and dropped files.
+    pub audio_track: Option<&'static str>,
+    /// Binds `GpuContext::keyboard` and makes the canvas focusable.
+    pub keyboard: bool,
+    /// Binds `GpuContext::pointer`; the canvas captures drags and wheel scrolls.
+    pub pointer: bool,
+}
+
+/// What a work needs from the device. The default is the WebGL2 baseline every backend provides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Requirements {
+    pub features: Features,
+    pub limits: Limits,
+    /// E.g. `DownlevelFlags::COMPUTE_SHADERS`, which WebGL2 lacks.
+    pub downlevel_flags: DownlevelFlags,
+}
+
+impl Default for Requirements {
+    fn default() -> Self {
+        Self {
+            features: Features::empty(),
+            limits: Limits::downlevel_webgl2_defaults(),
+            downlevel_flags: DownlevelFlags::empty(),
+        }
+    }
+}
+
+impl Requirements {
+    /// Lists what `adapter` lacks, if anything.
+    pub fn missing(&self, adapter: &Adapter) -> Option<String> {
+        let mut missing = Vec::new();
+        let features = self.features.difference(adapter.features());
+        if !features.is_empty() {
+            missing.push(format!("features {features:?}"));
+        }
+        self.limits.check_limits_with_fail_fn(&adapter.limits(), false, |name, required, allowed| {
+            missing.push(format!("{name} {required} (up to {allowed})"));
+        });
+        let downlevel_flags = self.downlevel_flags.difference(adapter.get_downlevel_capabilities().flags);
+        if !downlevel_flags.is_empty() {
+            missing.push(format!("{downlevel_flags:?}"));
+        }
+        (!missing.is_empty()).then(|| missing.join(", "))
+    }
+}
+
+pub trait Shader {
+    fn new(ctx: &GpuContext) -> Self where Self: Sized;
+
+    fn inputs() -> Inputs where Self: Sized {
+        Inputs::default()
+    }
+
+    fn requirements() -> Requirements where Self: Sized {
+        Requirements::default()
+    }
+
+    /// Encodes and submits one frame into `target`.
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput);
+}
+
+/// Why a GPU context couldn't be created. Pages show it instead of the work; nothing else depends on the GPU.
+#[derive(Clone, Debug)]
+pub enum GpuInitError {
+    /// No WebGPU or WebGL2 in the browser, or GPU access is blocked.
+    NoAdapter,
+    CreateSurface(CreateSurfaceError),
+    /// The adapter offers no format, present mode or alpha mode for the canvas.
+    IncompatibleSurface,
+    /// The adapter lacks features or limits the work requires.
+    Unsupported { backend: Backend, missing: String },
+    RequestDevice(RequestDeviceError),
+}
+
+impl fmt::Display for GpuInitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuInitError::NoAdapter => write!(f, "No GPU is available. The browser may not support WebGPU or WebGL2, or hardware acceleration may be turned off."),
+            GpuInitError::CreateSurface(error) => write!(f, "The canvas can't be rendered to: {error}"),
+            GpuInitError::IncompatibleSurface => write!(f, "The GPU can't present to the canvas."),
+            GpuInitError::Unsupported { backend: Backend::Gl, missing } => write!(f, "This work needs {missing}, which WebGL2 lacks. It runs in browsers with WebGPU."),
+            GpuInitError::Unsupported { backend, missing } => write!(f, "This work needs {missing}, which this GPU lacks on {backend}."),
+            GpuInitError::RequestDevice(error) => write!(f, "The GPU refused to start: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for GpuInitError {}
+
+/// Adapter and device that can be shared by several surfaces.
+pub struct Gpu {
+    pub instance: Instance,
+    pub adapter: Adapter,
+    pub device: Rc<Device>,
+    pub queue: Rc<Queue>,
+    /// Set by the device-lost callback, which has to be `Send`.
+    lost: Arc<AtomicBool>,
+}
+
+impl Gpu {
+    pub async fn new(instance: Instance, compatible_surface: Option<&Surface<'_>>, requirements: &Requirements) -> Result<Self, GpuInitError> {
+        let adapter = instance.request_adapter(&RequestAdapterOptions {
+            power_preference: PowerPreference::default(),
+            compatible_surface,
+            force_fallback_adapter: false,
+        })
+            .await
+            .ok_or(GpuInitError::NoAdapter)?;
+
+        Self::with_adapter(instance, adapter, requirements).await
+    }
+
+    /// Prefers the software fallback adapter so it also runs on machines without a GPU.
+    pub async fn headless(requirements: &Requirements) -> Result<Self, GpuInitError> {
+        let instance = Instance::default();
+        let mut adapter = None;
+        for force_fallback_adapter in [true, false] {
+            adapter = instance.request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::default(),
+                compatible_surface: None,
+                force_fallback_adapter,
+            }).await;
+            if adapter.as_ref().is_some_and(|adapter| requirements.missing(adapter).is_none()) {
+                break;
+            }
+        }
+        let adapter = adapter.ok_or(GpuInitError::NoAdapter)?;
+
+        Self::with_adapter(instance, adapter, requirements).await
+    }
+
+    pub async fn with_adapter(instance: Instance, adapter: Adapter, requirements: &Requirements) -> Result<Self, GpuInitError> {
+        if let Some(missing) = requirements.missing(&adapter) {
+            return Err(GpuInitError::Unsupported { backend: adapter.get_info().backend, missing });
+        }
+        let (device, queue) = adapter.request_device(&DeviceDescriptor {
+            label: None,
+            // Timestamps are optional, only for the timing overlay
+            required_features: requirements.features | (adapter.features() & Features::TIMESTAMP_QUERY),
+            required_limits: requirements.limits.clone()
+                .using_resolution(adapter.limits()),
+        }, None)
+            .await
+            .map_err(GpuInitError::RequestDevice)?;
+
+        let lost = Arc::new(AtomicBool::new(false));
+        let lost_flag = lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            // Dropping the device or replacing the callback also ends up here
+            if !matches!(reason, DeviceLostReason::Dropped | DeviceLostReason::ReplacedCallback) {
+                eprintln!("GPU device lost ({reason:?}): {message}");
+                lost_flag.store(true, Ordering::Release);
+            }
+        });
+
+        Ok(Self {
+            instance,
+            adapter,
+            device: Rc::new(device),
+            queue: Rc::new(queue),
+            lost,
+        })
+    }
+
+    /// WebGL ties the adapter to the canvas it was created for, so other canvases can't share it.
+    pub fn supports(&self, surface: &Surface) -> bool {
+        self.adapter.is_surface_supported(surface)
+    }
+
+    /// Once lost, the device and everything created on it has to be rebuilt.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire)
+    }
+
+    /// Whether the device was created with everything a work asks for.
+    pub fn satisfies(&self, requirements: &Requirements) -> bool {
+        self.device.features().contains(requirements.features)
+            && requirements.limits.check_limits(&self.device.limits())
+            && self.adapter.get_downlevel_capabilities().flags.contains(requirements.downlevel_flags)
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.adapter.get_info().backend
+    }
+}
+
+/// Rounds `size * pixel_ratio` to whole pixels, scaled down uniformly to fit `max_dimension`.
+pub fn physical_size(size: (u32, u32), pixel_ratio: f64, max_dimension: u32) -> (u32, u32) {
+    let width = size.0 as f64 * pixel_ratio;
+    let height = size.1 as f64 * pixel_ratio;
+    let fit = (max_dimension as f64 / width.max(height)).min(1.0);
+    let clamp = |length: f64| ((length * fit).round() as u32).clamp(1, max_dimension);
+    (clamp(width), clamp(height))
+}
+
+pub struct GpuContext<'a> {
+    /// `None` for a headless context, which renders into caller-owned textures.
+    pub surface: Option<Surface<'a>>,
+    pub device: Rc<Device>,
+    pub queue: Rc<Queue>,
+    pub config: SurfaceConfiguration,
+    /// CSS size; `config` holds the physical one.
+    pub size: (u32, u32),
+    pub pixel_ratio: f64,
+    pub palette: PaletteBinding,
+    pub audio: AudioBinding,
+    pub keyboard: KeyboardBinding,
+    pub pointer: PointerBinding,
+    pub adapter_info: AdapterInfo,
+    /// `None` where the device can't measure GPU time.
+    pub timer: Option<GpuTimer>,
+    lost: Arc<AtomicBool>,
+}
+
+impl<'a> GpuContext<'a> {
+    /// Renders into `surface` with `gpu`'s device, which several canvases may share.
+    /// Fails when the adapter reports no way to present to the surface.
+    pub fn with_gpu(gpu: &Gpu, surface: Surface<'a>, size: (u32, u32), pixel_ratio: f64) -> Result<Self, GpuInitError> {
+        let surface_capabilities = surface.get_capabilities(&gpu.adapter);
+        let (Some(&first_format), Some(&present_mode), Some(&alpha_mode)) = (
+            surface_capabilities.formats.first(),
+            surface_capabilities.present_modes.first(),
+            surface_capabilities.alpha_modes.first(),
+        ) else {
+            return Err(GpuInitError::IncompatibleSurface);
+        };
+        let surface_format = surface_capabilities.formats.iter()
+            .find(|f| f.is_srgb())
+            .copied()
+            .unwrap_or(first_format);
+        let (width, height) = physical_size(size, pixel_ratio, gpu.device.limits().max_texture_dimension_2d);
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width,
+            height,
+            present_mode,
+            alpha_mode,
+            view_formats: if !surface_format.is_srgb() {
+                vec![surface_format.add_srgb_suffix()]
+            } else {
+                vec![]
+            },
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(&gpu.device, &config);
+
+        Ok(Self {
+            surface: Some(surface),
+            palette: PaletteBinding::new(&gpu.device, Palette::default()),
+            audio: AudioBinding::new(&gpu.device),
+            keyboard: KeyboardBinding::new(&gpu.device),
+            pointer: PointerBinding::new(&gpu.device),
+            adapter_info: gpu.adapter.get_info(),
+            timer: GpuTimer::new(&gpu.device, &gpu.queue),
+            lost: gpu.lost.clone(),
+            device: gpu.device.clone(),
+            queue: gpu.queue.clone(),
+            config,
+            size,
+            pixel_ratio,
+        })
+    }
+
+    /// Surface-less context for offscreen rendering into caller-owned textures.
+    pub async fn headless(size: (u32, u32), requirements: &Requirements) -> Result<Self, GpuInitError> {
+        let gpu = Gpu::headless(requirements).await?;
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            format: TextureFormat::Rgba8UnormSrgb,
+            width: size.0,
+            height: size.1,
+            present_mode: PresentMode::Fifo,
+            alpha_mode: CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        Ok(Self {
+            surface: None,
+            palette: PaletteBinding::new(&gpu.device, Palette::default()),
+            audio: AudioBinding::new(&gpu.device),
+            keyboard: KeyboardBinding::new(&gpu.device),
+            pointer: PointerBinding::new(&gpu.device),
+            adapter_info: gpu.adapter.get_info(),
+            // Nothing reads GPU timings offscreen
+            timer: None,
+            lost: gpu.lost,
+            device: gpu.device,
+            queue: gpu.queue,
+            config,
+            size,
+            pixel_ratio: 1.0,
+        })
+    }
+
+    pub fn resize(&mut self, size: (u32, u32), pixel_ratio: f64) {
+        if self.size != size || self.pixel_ratio != pixel_ratio {
+            self.size = size;
+            self.pixel_ratio = pixel_ratio;
+            (self.config.width, self.config.height) = physical_size(size, pixel_ratio, self.device.limits().max_texture_dimension_2d);
+            self.reconfigure();
+        }
+    }
+
+    /// Configures the surface again, e.g. after it was reported lost or outdated.
+    pub fn reconfigure(&self) {
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+    }
+
+    pub fn is_device_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire)
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette.set(&self.queue, palette);
+    }
+
+    /// sRGB view of the surface format. WebGPU canvases only offer linear formats,
+    /// so pipelines and views use this rather than `config.format`.
+    pub fn target_format(&self) -> TextureFormat {
+        self.config.format.add_srgb_suffix()
+    }
+
+    /// Pass to the work's main render pass to have its GPU time measured.
+    pub fn timestamp_writes(&self) -> Option<RenderPassTimestampWrites<'_>> {
+        self.timer.as_ref().map(GpuTimer::timestamp_writes)
+    }
+
+    /// Call after the pass that got `timestamp_writes`, before finishing the encoder.
+    pub fn resolve_timestamps(&self, encoder: &mut CommandEncoder) {
+        if let Some(timer) = &self.timer {
+            timer.resolve(encoder);
+        }
+    }
+
+    /// Background of the active palette, for works that clear the whole target.
+    pub fn clear_color(&self) -> Color {
+        self.palette.palette.base()
+    }
+}
@@ -0,0 +1,137 @@
+use catppuccin::{ColorName, Flavor, PALETTE};
+use wgpu::{*, util::*};
+
+/// Same order as the fields of `Palette` in `palette.wgsl`.
+const COLOR_NAMES: [ColorName; 26] = [
+    ColorName::Rosewater,
+    ColorName::Flamingo,
+    ColorName::Pink,
+    ColorName::Mauve,
+    ColorName::Red,
+    ColorName::Maroon,
+    ColorName::Peach,
+    ColorName::Yellow,
+    ColorName::Green,
+    ColorName::Teal,
+    ColorName::Sky,
+    ColorName::Sapphire,
+    ColorName::Blue,
+    ColorName::Lavender,
+    ColorName::Text,
+    ColorName::Subtext1,
+    ColorName::Subtext0,
+    ColorName::Overlay2,
+    ColorName::Overlay1,
+    ColorName::Overlay0,
+    ColorName::Surface2,
+    ColorName::Surface1,
+    ColorName::Surface0,
+    ColorName::Base,
+    ColorName::Mantle,
+    ColorName::Crust,
+];
+
+const BASE: usize = 23;
+
+/// WGSL declaration of the palette block.
+pub const PALETTE_WGSL: &str = include_str!("./palette.wgsl");
+
+/// Catppuccin flavor in linear RGB, laid out for a uniform buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Palette {
+    colors: [[f32; 4]; 26],
+    dark: u32,
+    _padding: [u32; 3],
+}
+
+impl Palette {
+    pub fn new(flavor: &Flavor) -> Self {
+        Self {
+            colors: COLOR_NAMES.map(|name| {
+                let rgb = flavor.get_color(name).rgb;
+                [srgb_to_linear(rgb.r), srgb_to_linear(rgb.g), srgb_to_linear(rgb.b), 1.0]
+            }),
+            dark: flavor.dark as u32,
+            _padding: [0; 3],
+        }
+    }
+
+    pub fn base(&self) -> Color {
+        let [r, g, b, a] = self.colors[BASE];
+        Color { r: r as f64, g: g as f64, b: b as f64, a: a as f64 }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(&PALETTE.mocha)
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Uniform buffer and bind group of the palette. Works opt in by adding `bind_group_layout`
+/// to their pipeline layout and declaring `PALETTE_WGSL` at the matching group.
+pub struct PaletteBinding {
+    pub palette: Palette,
+    pub bind_group_layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+    buffer: Buffer,
+}
+
+impl PaletteBinding {
+    pub fn new(device: &Device, palette: Palette) -> Self {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("palette"),
+            contents: bytemuck::cast_slice(&[palette]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("palette"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("palette"),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }
+            ],
+            layout: &bind_group_layout,
+        });
+
+        Self {
+            palette,
+            bind_group_layout,
+            bind_group,
+            buffer,
+        }
+    }
+
+    pub fn set(&mut self, queue: &Queue, palette: Palette) {
+        if self.palette != palette {
+            self.palette = palette;
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[palette]));
+        }
+    }
+}
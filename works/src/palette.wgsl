@@ -0,0 +1,32 @@
+// Catppuccin palette of the active theme, in linear RGB (alpha is always 1.0)
+struct Palette {
+    rosewater: vec4<f32>,
+    flamingo: vec4<f32>,
+    pink: vec4<f32>,
+    mauve: vec4<f32>,
+    red: vec4<f32>,
+    maroon: vec4<f32>,
+    peach: vec4<f32>,
+    yellow: vec4<f32>,
+    green: vec4<f32>,
+    teal: vec4<f32>,
+    sky: vec4<f32>,
+    sapphire: vec4<f32>,
+    blue: vec4<f32>,
+    lavender: vec4<f32>,
+    text: vec4<f32>,
+    subtext1: vec4<f32>,
+    subtext0: vec4<f32>,
+    overlay2: vec4<f32>,
+    overlay1: vec4<f32>,
+    overlay0: vec4<f32>,
+    surface2: vec4<f32>,
+    surface1: vec4<f32>,
+    surface0: vec4<f32>,
+    base: vec4<f32>,
+    mantle: vec4<f32>,
+    crust: vec4<f32>,
+    // 1 for dark flavors, 0 for light ones
+    dark: u32,
+}
+
@@ -0,0 +1,311 @@
+use wgpu::{*, util::*};
+
+/// Touch points beyond this are ignored.
+pub const MAX_TOUCHES: usize = 10;
+
+/// WGSL declarations of the pointer bindings.
+pub const POINTER_WGSL: &str = include_str!("./pointer.wgsl");
+
+/// A pointer event, in CSS pixels from the top-left of the canvas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointerEvent {
+    pub id: i32,
+    pub position: (f32, f32),
+    /// `PointerEvent.buttons` bitmask.
+    pub buttons: u32,
+    pub pressure: f32,
+    pub is_primary: bool,
+    pub is_touch: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Touch {
+    id: i32,
+    position: (f32, f32),
+    pressure: f32,
+}
+
+/// Buttons, press origin, drag, wheel and touches, accumulated from pointer events between frames.
+/// Only the primary pointer drives position, buttons and dragging; touches are listed separately.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointerState {
+    position: (f32, f32),
+    buttons: u32,
+    click: Option<(f32, f32)>,
+    wheel: (f32, f32),
+    touches: Vec<Touch>,
+    changed: bool,
+}
+
+impl PointerState {
+    pub fn pointer_down(&mut self, event: PointerEvent) {
+        if event.is_primary {
+            self.position = event.position;
+            self.buttons = event.buttons;
+            self.click = Some(event.position);
+        }
+        if event.is_touch && self.touches.len() < MAX_TOUCHES && !self.touches.iter().any(|touch| touch.id == event.id) {
+            self.touches.push(Touch { id: event.id, position: event.position, pressure: event.pressure });
+        }
+        self.changed = true;
+    }
+
+    pub fn pointer_move(&mut self, event: PointerEvent) {
+        if event.is_primary {
+            self.position = event.position;
+            self.buttons = event.buttons;
+        }
+        if let Some(touch) = self.touches.iter_mut().find(|touch| touch.id == event.id) {
+            touch.position = event.position;
+            touch.pressure = event.pressure;
+        }
+        self.changed = true;
+    }
+
+    /// Also for `pointercancel`.
+    pub fn pointer_up(&mut self, event: PointerEvent) {
+        if event.is_primary {
+            self.position = event.position;
+            self.buttons = event.buttons;
+            if event.buttons == 0 {
+                self.click = None;
+            }
+        }
+        self.touches.retain(|touch| touch.id != event.id);
+        self.changed = true;
+    }
+
+    /// Deltas in pixels; the total keeps growing so works can zoom or pan with it.
+    pub fn wheel(&mut self, delta: (f32, f32)) {
+        self.wheel.0 += delta.0;
+        self.wheel.1 += delta.1;
+        self.changed = true;
+    }
+
+    /// Returns the uniform and touch texels to upload if anything changed since the last call.
+    /// `scale` converts CSS pixels to framebuffer pixels.
+    fn take_frame(&mut self, scale: f32) -> Option<(PointerUniform, Vec<f32>)> {
+        if !self.changed {
+            return None;
+        }
+        self.changed = false;
+
+        let scaled = |(x, y): (f32, f32)| [x * scale, y * scale];
+        let click = self.click.unwrap_or(self.position);
+        let uniform = PointerUniform {
+            position: scaled(self.position),
+            click: scaled(click),
+            drag: match self.click {
+                Some(click) => scaled((self.position.0 - click.0, self.position.1 - click.1)),
+                None => [0.0, 0.0],
+            },
+            wheel: [self.wheel.0, self.wheel.1],
+            buttons: self.buttons,
+            pressed: self.click.is_some() as u32,
+            touch_count: self.touches.len() as u32,
+            _padding: 0,
+        };
+        let mut touches = vec![0.0; MAX_TOUCHES * 4];
+        for (texel, touch) in touches.chunks_mut(4).zip(&self.touches) {
+            let [x, y] = scaled(touch.position);
+            texel.copy_from_slice(&[x, y, touch.pressure, 1.0]);
+        }
+
+        Some((uniform, touches))
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct PointerUniform {
+    position: [f32; 2],
+    click: [f32; 2],
+    drag: [f32; 2],
+    wheel: [f32; 2],
+    buttons: u32,
+    pressed: u32,
+    touch_count: u32,
+    _padding: u32,
+}
+
+/// Pointer uniform plus a `MAX_TOUCHES`×1 texture of touch points. A texture rather than
+/// a storage buffer because WebGL2 has none. Works opt in through `Inputs::pointer`.
+pub struct PointerBinding {
+    pub bind_group_layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+    buffer: Buffer,
+    touches_texture: Texture,
+}
+
+impl PointerBinding {
+    pub fn new(device: &Device) -> Self {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("pointer"),
+            contents: bytemuck::cast_slice(&[PointerUniform::default()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let touches_texture = device.create_texture(&TextureDescriptor {
+            label: Some("touches"),
+            size: Extent3d {
+                width: MAX_TOUCHES as u32,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba32Float,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("pointer"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("pointer"),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&touches_texture.create_view(&TextureViewDescriptor::default())),
+                },
+            ],
+            layout: &bind_group_layout,
+        });
+
+        Self {
+            bind_group_layout,
+            bind_group,
+            buffer,
+            touches_texture,
+        }
+    }
+
+    pub fn update(&self, queue: &Queue, state: &mut PointerState, scale: f32) {
+        let Some((uniform, touches)) = state.take_frame(scale) else { return };
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[uniform]));
+        queue.write_texture(
+            self.touches_texture.as_image_copy(),
+            bytemuck::cast_slice(&touches),
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some((MAX_TOUCHES * 16) as u32),
+                rows_per_image: None,
+            },
+            self.touches_texture.size(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse(position: (f32, f32), buttons: u32) -> PointerEvent {
+        PointerEvent { id: 1, position, buttons, pressure: 0.5, is_primary: true, is_touch: false }
+    }
+
+    fn touch(id: i32, position: (f32, f32)) -> PointerEvent {
+        PointerEvent { id, position, buttons: 1, pressure: 0.5, is_primary: id == 0, is_touch: true }
+    }
+
+    #[test]
+    fn nothing_to_upload_without_events() {
+        let mut state = PointerState::default();
+        assert_eq!(state.take_frame(1.0), None);
+        state.pointer_move(mouse((4.0, 4.0), 0));
+        assert!(state.take_frame(1.0).is_some());
+        assert_eq!(state.take_frame(1.0), None);
+    }
+
+    #[test]
+    fn drags_from_the_press_until_release() {
+        let mut state = PointerState::default();
+        state.pointer_move(mouse((10.0, 10.0), 0));
+        let (uniform, _) = state.take_frame(1.0).unwrap();
+        assert_eq!((uniform.pressed, uniform.drag, uniform.click), (0, [0.0, 0.0], [10.0, 10.0]));
+
+        state.pointer_down(mouse((20.0, 30.0), 1));
+        state.pointer_move(mouse((25.0, 20.0), 1));
+        let (uniform, _) = state.take_frame(1.0).unwrap();
+        assert_eq!((uniform.pressed, uniform.buttons), (1, 1));
+        assert_eq!((uniform.position, uniform.click, uniform.drag), ([25.0, 20.0], [20.0, 30.0], [5.0, -10.0]));
+
+        state.pointer_up(mouse((26.0, 20.0), 0));
+        let (uniform, _) = state.take_frame(1.0).unwrap();
+        assert_eq!((uniform.pressed, uniform.buttons, uniform.drag), (0, 0, [0.0, 0.0]));
+        assert_eq!(uniform.click, uniform.position);
+    }
+
+    #[test]
+    fn other_buttons_keep_the_drag() {
+        let mut state = PointerState::default();
+        state.pointer_down(mouse((0.0, 0.0), 1));
+        state.pointer_down(mouse((0.0, 0.0), 3));
+        state.pointer_up(mouse((4.0, 0.0), 1));
+        let (uniform, _) = state.take_frame(1.0).unwrap();
+        assert_eq!((uniform.pressed, uniform.drag), (1, [4.0, 0.0]));
+    }
+
+    #[test]
+    fn scales_positions_but_not_the_wheel() {
+        let mut state = PointerState::default();
+        state.pointer_down(mouse((10.0, 20.0), 1));
+        state.pointer_move(mouse((15.0, 20.0), 1));
+        state.wheel((0.0, 100.0));
+        state.wheel((0.0, -40.0));
+        let (uniform, _) = state.take_frame(2.0).unwrap();
+        assert_eq!((uniform.position, uniform.click, uniform.drag), ([30.0, 40.0], [20.0, 40.0], [10.0, 0.0]));
+        assert_eq!(uniform.wheel, [0.0, 60.0]);
+    }
+
+    #[test]
+    fn lists_touches_up_to_the_cap() {
+        let mut state = PointerState::default();
+        for id in 0..(MAX_TOUCHES as i32 + 2) {
+            state.pointer_down(touch(id, (id as f32, 1.0)));
+        }
+        // A repeated down doesn't add the same touch twice
+        state.pointer_down(touch(3, (3.0, 1.0)));
+        let (uniform, touches) = state.take_frame(1.0).unwrap();
+        assert_eq!(uniform.touch_count, MAX_TOUCHES as u32);
+        assert_eq!(touches.len(), MAX_TOUCHES * 4);
+        assert_eq!(&touches[(MAX_TOUCHES - 1) * 4..], [MAX_TOUCHES as f32 - 1.0, 1.0, 0.5, 1.0]);
+
+        // Lifting a listed touch frees a slot; moving an ignored one changes nothing
+        state.pointer_move(touch(MAX_TOUCHES as i32, (50.0, 50.0)));
+        state.pointer_up(touch(2, (2.0, 1.0)));
+        state.pointer_move(touch(1, (8.0, 9.0)));
+        let (uniform, touches) = state.take_frame(2.0).unwrap();
+        assert_eq!(uniform.touch_count, MAX_TOUCHES as u32 - 1);
+        assert_eq!(&touches[4..8], [16.0, 18.0, 0.5, 1.0]);
+        assert_eq!(&touches[(MAX_TOUCHES - 1) * 4..], [0.0; 4]);
+        state.pointer_down(touch(20, (5.0, 5.0)));
+        assert_eq!(state.take_frame(1.0).unwrap().0.touch_count, MAX_TOUCHES as u32);
+    }
+}
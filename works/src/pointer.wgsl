@@ -0,0 +1,21 @@
+// Pointer input in framebuffer pixels from the top-left, bound by works that opt in with `Inputs::pointer`:
+//   @group(N) @binding(0) var<uniform> pointer: Pointer;
+//   @group(N) @binding(1) var touches_texture: texture_2d<f32>;  // 10×1 texels of (x, y, pressure, active)
+struct Pointer {
+    position: vec2<f32>,
+    // Where the current press started; equals `position` while released
+    click: vec2<f32>,
+    drag: vec2<f32>,
+    // Accumulated wheel deltas
+    wheel: vec2<f32>,
+    // `PointerEvent.buttons` bitmask
+    buttons: u32,
+    pressed: u32,
+    touch_count: u32,
+}
+
+// (x, y, pressure, active) of the `index`th touch, in the order the fingers went down
+fn touch(index: u32) -> vec4<f32> {
+    return textureLoad(touches_texture, vec2<u32>(index, 0u), 0);
+}
+
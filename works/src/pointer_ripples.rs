@@ -0,0 +1,35 @@
+use wgpu::*;
+use super::{
+    base::fragment::{FragmentWork, InputBinding},
+    FrameInput,
+    GpuContext,
+    Inputs,
+    Shader,
+    PALETTE_WGSL,
+    POINTER_WGSL,
+};
+
+/// Seconds into the work at which the gallery thumbnail is rendered.
+pub const THUMBNAIL_TIME: f32 = 1.0;
+
+/// The WGSL this work compiles, in order.
+pub const SOURCE: &[&str] = &[PALETTE_WGSL, POINTER_WGSL, include_str!("./pointer_ripples.wgsl")];
+
+pub struct ShaderWork(FragmentWork);
+
+impl Shader for ShaderWork {
+    fn new(ctx: &GpuContext) -> Self {
+        Self(FragmentWork::new(ctx, "pointer_ripples.wgsl", SOURCE, InputBinding::Pointer))
+    }
+
+    fn inputs() -> Inputs {
+        Inputs {
+            pointer: true,
+            ..Inputs::default()
+        }
+    }
+
+    fn render(&mut self, ctx: &GpuContext, target: &Texture, input: &FrameInput) {
+        self.0.render(ctx, target, input)
+    }
+}
@@ -0,0 +1,63 @@
+@group(0) @binding(0)
+var<uniform> palette: Palette;
+
+struct Uniform {
+    resolution: vec2<f32>,
+    time: f32,
+}
+
+@group(1) @binding(0)
+var<uniform> uniforms: Uniform;
+
+@group(2) @binding(0)
+var<uniform> pointer: Pointer;
+@group(2) @binding(1)
+var touches_texture: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) i_vertex: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((i_vertex << 1u) & 2u), f32(i_vertex & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn segment_distance(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let ab = b - a;
+    let t = clamp(dot(p - a, ab) / max(dot(ab, ab), 1e-4), 0.0, 1.0);
+    return length(p - a - ab * t);
+}
+
+fn disc(distance_to_center: f32, radius: f32) -> f32 {
+    return 1.0 - smoothstep(radius - 1.0, radius + 1.0, distance_to_center);
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let scale = uniforms.resolution.y / 720.0;
+    var color = palette.base;
+
+    // Ripples spread from the pointer; scrolling the wheel spaces them out or packs them together
+    let spacing = 48.0 * scale * exp2(clamp(-pointer.wheel.y / 600.0, -2.0, 2.0));
+    let to_pointer = distance(pos.xy, pointer.position);
+    let wave = 0.5 + 0.5 * cos(6.2831853 * (to_pointer / spacing - uniforms.time * 0.5));
+    let fade = exp(-to_pointer / (uniforms.resolution.y * 0.5));
+    let ripple_color = select(palette.blue, palette.mauve, pointer.pressed != 0u);
+    color = mix(color, ripple_color, pow(wave, 8.0) * fade * 0.8);
+
+    // Dragging stretches a line back to where the press started
+    if pointer.pressed != 0u {
+        let line = disc(segment_distance(pos.xy, pointer.click, pointer.position), 3.0 * scale);
+        color = mix(color, palette.peach, line);
+        color = mix(color, palette.peach, disc(distance(pos.xy, pointer.click), 10.0 * scale));
+    }
+
+    // Every finger on a touch screen gets a dot sized by its pressure
+    for (var i = 0u; i < pointer.touch_count; i++) {
+        let finger = touch(i);
+        let radius = (24.0 + 40.0 * finger.z) * scale;
+        color = mix(color, palette.green, disc(distance(pos.xy, finger.xy), radius) * 0.8);
+    }
+
+    color = mix(color, palette.text, disc(to_pointer, 6.0 * scale));
+
+    return vec4<f32>(color.rgb, 1.0);
+}
@@ -0,0 +1,100 @@
+use std::sync::{Arc, atomic::{AtomicU8, Ordering}};
+use wgpu::*;
+
+const IDLE: u8 = 0;
+const COPIED: u8 = 1;
+const MAPPING: u8 = 2;
+const READY: u8 = 3;
+
+/// Two timestamps around a work's main render pass.
+const QUERY_COUNT: u32 = 2;
+const BUFFER_SIZE: BufferAddress = QUERY_COUNT as BufferAddress * 8;
+
+/// Measures how long a render pass takes on the GPU. Only exists on devices with `Features::TIMESTAMP_QUERY`.
+/// The readback is asynchronous, so results trail the frame they were measured in by a few frames.
+pub struct GpuTimer {
+    query_set: QuerySet,
+    resolve_buffer: Buffer,
+    readback_buffer: Buffer,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+    /// Shared with the `map_async` callback, which has to be `Send` off the web.
+    state: Arc<AtomicU8>,
+    last_pass_time: Option<f32>,
+}
+
+impl GpuTimer {
+    pub fn new(device: &Device, queue: &Queue) -> Option<Self> {
+        if !device.features().contains(Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        Some(Self {
+            query_set: device.create_query_set(&QuerySetDescriptor {
+                label: Some("pass timestamps"),
+                ty: QueryType::Timestamp,
+                count: QUERY_COUNT,
+            }),
+            resolve_buffer: device.create_buffer(&BufferDescriptor {
+                label: Some("timestamp resolve"),
+                size: BUFFER_SIZE,
+                usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: device.create_buffer(&BufferDescriptor {
+                label: Some("timestamp readback"),
+                size: BUFFER_SIZE,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+            state: Arc::new(AtomicU8::new(IDLE)),
+            last_pass_time: None,
+        })
+    }
+
+    pub fn timestamp_writes(&self) -> RenderPassTimestampWrites<'_> {
+        RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        }
+    }
+
+    /// Copies the timestamps for readback unless the previous readback is still in flight.
+    pub fn resolve(&self, encoder: &mut CommandEncoder) {
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        if self.state.compare_exchange(IDLE, COPIED, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+            encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, BUFFER_SIZE);
+        }
+    }
+
+    /// Call after the frame is submitted. Starts the readback of freshly copied timestamps
+    /// and picks up a finished one.
+    pub fn poll(&mut self) {
+        match self.state.load(Ordering::Acquire) {
+            COPIED => {
+                self.state.store(MAPPING, Ordering::Release);
+                let state = self.state.clone();
+                self.readback_buffer.slice(..).map_async(MapMode::Read, move |result| {
+                    state.store(if result.is_ok() { READY } else { IDLE }, Ordering::Release);
+                });
+            }
+            READY => {
+                {
+                    let data = self.readback_buffer.slice(..).get_mapped_range();
+                    let timestamps: &[u64] = bytemuck::cast_slice(&data);
+                    let ticks = timestamps[1].saturating_sub(timestamps[0]);
+                    self.last_pass_time = Some(ticks as f32 * self.period / 1_000_000.0);
+                }
+                self.readback_buffer.unmap();
+                self.state.store(IDLE, Ordering::Release);
+            }
+            _ => {}
+        }
+    }
+
+    /// GPU time of the latest measured pass, in milliseconds.
+    pub fn last_pass_time(&self) -> Option<f32> {
+        self.last_pass_time
+    }
+}